@@ -19,11 +19,29 @@ fn main() {
     // Put the linker script somewhere the linker can find it
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
     let link_x = include_bytes!("link.x.in");
-    let mut f = if env::var_os("CARGO_FEATURE_DEVICE").is_some() {
-        let mut f = File::create(out.join("link.x")).unwrap();
+    let mut f = File::create(out.join("link.x")).unwrap();
 
-        f.write_all(link_x).unwrap();
+    // `link.x.in` only reserves the stack guard region when the `stack-guard` feature is enabled.
+    // The smallest MPU region is 256 bytes on ARMv6-M, and 32 bytes otherwise.
+    let stack_guard_min_size = if target.starts_with("thumbv6m-") {
+        256
+    } else {
+        32
+    };
+    writeln!(
+        f,
+        r#"/* Set from the `stack-guard` feature of cortex-m-rt */
+__stack_guard_enabled = {};
+/* Smallest MPU region of the target */
+__stack_guard_min_size = {};
+"#,
+        env::var_os("CARGO_FEATURE_STACK_GUARD").is_some() as u8,
+        stack_guard_min_size
+    )
+    .unwrap();
+    f.write_all(link_x).unwrap();
 
+    if env::var_os("CARGO_FEATURE_DEVICE").is_some() {
         // *IMPORTANT*: The weak aliases (i.e. `PROVIDED`) must come *after* `EXTERN(__INTERRUPTS)`.
         // Otherwise the linker will ignore user defined interrupts and always populate the table
         // with the weak aliases.
@@ -35,12 +53,7 @@ fn main() {
 INCLUDE device.x"#
         )
         .unwrap();
-        f
-    } else {
-        let mut f = File::create(out.join("link.x")).unwrap();
-        f.write_all(link_x).unwrap();
-        f
-    };
+    }
 
     println!("cargo:rustc-check-cfg=cfg(armv6m)");
     println!("cargo:rustc-check-cfg=cfg(armv7em)");
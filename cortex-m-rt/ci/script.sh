@@ -21,6 +21,8 @@ main() {
         divergent-default-handler
         divergent-exception
         entry-static
+        hard-fault-alias
+        hard-fault-extended
         hard-fault-trampoline
         main
         minimal
@@ -28,6 +30,7 @@ main() {
         pre_init
         qemu
         state
+        svc
         unsafe-default-handler
         unsafe-entry
         unsafe-exception
@@ -68,6 +71,10 @@ main() {
             cargo rustc --target "$TARGET" --example minimal --features "zero-init-ram,${needed_features}" --release -- $linker
             cargo rustc --target "$TARGET" --example minimal --features "set-vtor,${needed_features}" -- $linker
             cargo rustc --target "$TARGET" --example minimal --features "set-vtor,${needed_features}" --release -- $linker
+            cargo rustc --target "$TARGET" --example minimal --features "stack-guard,${needed_features}" -- $linker
+            cargo rustc --target "$TARGET" --example minimal --features "stack-guard,${needed_features}" --release -- $linker
+            cargo rustc --target "$TARGET" --example crash-record --features "crash-record,${needed_features}" -- $linker
+            cargo rustc --target "$TARGET" --example crash-record --features "crash-record,${needed_features}" --release -- $linker
         done
     fi
 
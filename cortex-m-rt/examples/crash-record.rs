@@ -0,0 +1,21 @@
+//! Retrieve the crash record saved by the default `HardFault` handler on the previous run.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use rt::crash_record::CrashRecord;
+use rt::entry;
+
+#[entry]
+fn main() -> ! {
+    if let Some(crash) = CrashRecord::take() {
+        let _pc = crash.frame().pc();
+        let _stack = crash.stack();
+    }
+
+    loop {}
+}
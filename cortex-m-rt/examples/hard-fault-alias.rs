@@ -0,0 +1,23 @@
+//! The HardFault handler frame is selected by its type, so aliases work too.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use rt::{entry, exception};
+
+type Frame = rt::ExtendedExceptionFrame;
+
+#[entry]
+fn main() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(ef: &Frame) -> ! {
+    let _psp = ef.exc_return().bits();
+    loop {}
+}
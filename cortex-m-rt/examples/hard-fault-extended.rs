@@ -0,0 +1,25 @@
+//! Use the extended exception frame in the HardFault handler.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use rt::{entry, exception, ExtendedExceptionFrame, StackPointer};
+
+#[entry]
+fn main() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(ef: &ExtendedExceptionFrame) -> ! {
+    let _pc = ef.frame().pc();
+    let _psp = ef.exc_return().stack_pointer() == StackPointer::Psp;
+    if let Some(fp) = ef.fp_context() {
+        let _fpscr = fp.fpscr();
+    }
+    loop {}
+}
@@ -0,0 +1,36 @@
+//! Dispatch supervisor calls to a syscall table from the SVCall handler.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use rt::svc::SyscallTable;
+use rt::{entry, exception, svc, ExceptionFrame};
+
+fn add([a, b, _, _]: [u32; 4]) -> u32 {
+    a.wrapping_add(b)
+}
+
+fn sum([a, b, c, d]: [u32; 4]) -> u32 {
+    a.wrapping_add(b).wrapping_add(c).wrapping_add(d)
+}
+
+static SYSCALLS: SyscallTable<2> = SyscallTable::new().with(0, add).with(1, sum);
+
+#[entry]
+fn main() -> ! {
+    let _three = svc!(0, 1, 2);
+    let _ten = svc!(1, 1, 2, 3, 4);
+    let _unknown = svc!(7);
+    loop {}
+}
+
+#[exception]
+unsafe fn SVCall(frame: &mut ExceptionFrame) {
+    if SYSCALLS.dispatch(frame).is_err() {
+        frame.set_r0(u32::MAX);
+    }
+}
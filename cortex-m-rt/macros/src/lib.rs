@@ -347,7 +347,8 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                 return parse::Error::new(
                     fspan,
                     if args.trampoline {
-                        "`HardFault` handler must have signature `unsafe fn(&ExceptionFrame) -> !`"
+                        "`HardFault` handler must have signature `unsafe fn(&ExceptionFrame) -> !` \
+                         or `unsafe fn(&ExtendedExceptionFrame) -> !`"
                     } else {
                         "`HardFault` handler must have signature `unsafe fn() -> !`"
                     },
@@ -364,6 +365,22 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 
                 let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
 
+                // The frame passed to the handler is built by the `HardFaultFrame` implementation of
+                // its argument type, so that aliases and re-exports of the frame types work too.
+                let frame_ty = match &f.sig.inputs[0] {
+                    FnArg::Typed(arg) => match arg.ty.as_ref() {
+                        Type::Reference(r) => r.elem.as_ref(),
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                };
+
+                let tramp_fn = quote!(
+                    unsafe extern "C" fn #tramp_ident(sp: *const u32, exc_return: u32) {
+                        <#frame_ty as ::cortex_m_rt::HardFaultFrame>::call(sp, exc_return, #ident)
+                    }
+                );
+
                 quote!(
                     #(#cfgs)*
                     #(#attrs)*
@@ -373,14 +390,13 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                     // because some hosted platforms (used to check the build)
                     // cannot handle the long link section names.
                     #[cfg_attr(target_os = "none", link_section = ".HardFault.user")]
-                    unsafe extern "C" fn #tramp_ident(frame: &::cortex_m_rt::ExceptionFrame) {
-                        #ident(frame)
-                    }
+                    #tramp_fn
 
                     #f
 
                     // HardFault exceptions are bounced through this trampoline which grabs the stack pointer at
-                    // the time of the exception and passes it to the user's HardFault handler in r0.
+                    // the time of the exception and passes it to the user's HardFault handler in r0, and
+                    // EXC_RETURN in r1.
                     // Depending on the stack mode in EXC_RETURN, fetches stack from either MSP or PSP.
                     core::arch::global_asm!(
                         ".cfi_sections .debug_frame
@@ -390,9 +406,9 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                         .thumb_func
                         .cfi_startproc
                         HardFault:",
-                           "mov r0, lr
-                            movs r1, #4
-                            tst r0, r1
+                           "mov r1, lr
+                            movs r0, #4
+                            tst r1, r0
                             bne 0f
                             mrs r0, MSP
                             b _HardFault
@@ -420,6 +436,84 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                 )
             }
         }
+        Exception::Other if ident_s == "SVCall" && !f.sig.inputs.is_empty() => {
+            let valid_signature = f.sig.constness.is_none()
+                && f.vis == Visibility::Inherited
+                && f.sig.abi.is_none()
+                && f.sig.inputs.len() == 1
+                && match &f.sig.inputs[0] {
+                    FnArg::Typed(arg) => match arg.ty.as_ref() {
+                        Type::Reference(r) => r.lifetime.is_none() && r.mutability.is_some(),
+                        _ => false,
+                    },
+                    _ => false,
+                }
+                && f.sig.generics.params.is_empty()
+                && f.sig.generics.where_clause.is_none()
+                && f.sig.variadic.is_none()
+                && match f.sig.output {
+                    ReturnType::Default => true,
+                    ReturnType::Type(_, ref ty) => match **ty {
+                        Type::Tuple(ref tuple) => tuple.elems.is_empty(),
+                        _ => false,
+                    },
+                };
+
+            if !valid_signature {
+                return parse::Error::new(
+                    fspan,
+                    "`SVCall` handler taking the exception frame must have signature \
+                     `[unsafe] fn(&mut ExceptionFrame)`",
+                )
+                .to_compile_error()
+                .into();
+            }
+
+            f.sig.ident = Ident::new(&format!("__cortex_m_rt_{}", f.sig.ident), Span::call_site());
+            let tramp_ident = Ident::new(&format!("{}_trampoline", f.sig.ident), Span::call_site());
+            let ident = &f.sig.ident;
+
+            let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
+
+            quote!(
+                #(#cfgs)*
+                #(#attrs)*
+                #[doc(hidden)]
+                #[export_name = "_SVCall"]
+                unsafe extern "C" fn #tramp_ident(frame: &mut ::cortex_m_rt::ExceptionFrame) {
+                    #ident(frame)
+                }
+
+                #f
+
+                // SVCall exceptions are bounced through this trampoline which grabs the stack pointer
+                // at the time of the exception and passes it to the user's SVCall handler in r0.
+                // Depending on the stack mode in EXC_RETURN, fetches stack from either MSP or PSP.
+                // The handler is reached with `bx`, which leaves EXC_RETURN in LR for its return.
+                core::arch::global_asm!(
+                    ".cfi_sections .debug_frame
+                    .section .text.SVCallTrampoline, \"ax\"
+                    .global SVCall
+                    .type SVCall,%function
+                    .thumb_func
+                    .cfi_startproc
+                    SVCall:",
+                       "mov r1, lr
+                        movs r0, #4
+                        tst r1, r0
+                        bne 0f
+                        mrs r0, MSP
+                        ldr r1, =_SVCall
+                        bx r1
+                    0:
+                        mrs r0, PSP
+                        ldr r1, =_SVCall
+                        bx r1",
+                    ".cfi_endproc
+                    .size SVCall, . - SVCall",
+                );
+            )
+        }
         Exception::NonMaskableInt | Exception::Other => {
             let valid_signature = f.sig.constness.is_none()
                 && f.vis == Visibility::Inherited
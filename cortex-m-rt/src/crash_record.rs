@@ -0,0 +1,320 @@
+//! Crash record captured by the default `HardFault` handler
+//!
+//! When the `crash-record` feature is enabled, the default `HardFault` handler (the one used when
+//! the application does not define `#[exception] unsafe fn HardFault`) saves a [`CrashRecord`] to
+//! the `.uninit` section and then requests a system reset through `SCB.AIRCR.SYSRESETREQ`. The
+//! record survives the reset and can be retrieved with [`CrashRecord::take`] once the application
+//! is running again.
+//!
+//! The record is protected by a magic word and a checksum, so the garbage left in RAM after a
+//! power-on reset is not mistaken for a crash.
+//!
+//! **NOTE:** The `zero-init-ram` feature clears the `.uninit` section on startup, and therefore
+//! also the crash record.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cortex_m_rt::crash_record::CrashRecord;
+//!
+//! if let Some(crash) = CrashRecord::take() {
+//!     // report the crash, e.g. over a serial port or a radio link
+//!     let _pc = crash.frame().pc();
+//!     let _vector = crash.active_vector();
+//!     let _stack = crash.stack();
+//! }
+//! ```
+//!
+//! On ARMv7-M and ARMv8-M Mainline the raw fault status registers can be decoded with
+//! `cortex_m::peripheral::scb::FaultStatus::from_registers`.
+
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+
+use crate::ExceptionFrame;
+
+/// Maximum number of stack words saved in a [`CrashRecord`].
+pub const STACK_DUMP_WORDS: usize = 32;
+
+/// "CRSH"
+const MAGIC: u32 = 0x4352_5348;
+
+/// State saved by the default `HardFault` handler when the `crash-record` feature is enabled.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct CrashRecord {
+    frame: ExceptionFrame,
+    exc_return: u32,
+    sp: u32,
+    vector: u32,
+    cfsr: u32,
+    hfsr: u32,
+    dfsr: u32,
+    mmfar: u32,
+    bfar: u32,
+    afsr: u32,
+    stack_len: u32,
+    stack: [u32; STACK_DUMP_WORDS],
+}
+
+#[repr(C)]
+struct Stored {
+    magic: u32,
+    record: CrashRecord,
+    checksum: u32,
+}
+
+#[cfg_attr(cortex_m, link_section = ".uninit.cortex_m_rt.CRASH_RECORD")]
+static mut CRASH_RECORD: MaybeUninit<Stored> = MaybeUninit::uninit();
+
+impl CrashRecord {
+    /// Returns the registers stacked on exception entry.
+    ///
+    /// The registers are all zero if the stack pointer did not point to RAM, or was not word
+    /// aligned, as the frame could not be read.
+    #[inline]
+    pub fn frame(&self) -> &ExceptionFrame {
+        &self.frame
+    }
+
+    /// Returns the `EXC_RETURN` value the `HardFault` handler was entered with.
+    #[inline]
+    pub fn exc_return(&self) -> u32 {
+        self.exc_return
+    }
+
+    /// Returns the stack pointer (MSP or PSP, as selected by `EXC_RETURN`) the frame was
+    /// stacked on.
+    #[inline]
+    pub fn stack_pointer(&self) -> u32 {
+        self.sp
+    }
+
+    /// Returns the number of the active exception (`ICSR.VECTACTIVE`).
+    ///
+    /// This is `3` unless the fault was raised while the debugger redirected it.
+    #[inline]
+    pub fn active_vector(&self) -> u32 {
+        self.vector
+    }
+
+    /// Returns the Configurable Fault Status Register.
+    ///
+    /// Always `0` on ARMv6-M and ARMv8-M Baseline, which do not implement it.
+    #[inline]
+    pub fn cfsr(&self) -> u32 {
+        self.cfsr
+    }
+
+    /// Returns the HardFault Status Register.
+    ///
+    /// Always `0` on ARMv6-M and ARMv8-M Baseline, which do not implement it.
+    #[inline]
+    pub fn hfsr(&self) -> u32 {
+        self.hfsr
+    }
+
+    /// Returns the Debug Fault Status Register.
+    ///
+    /// Always `0` on ARMv6-M and ARMv8-M Baseline.
+    #[inline]
+    pub fn dfsr(&self) -> u32 {
+        self.dfsr
+    }
+
+    /// Returns the MemManage Fault Address Register.
+    ///
+    /// Only meaningful if `CFSR.MMARVALID` is set.
+    #[inline]
+    pub fn mmfar(&self) -> u32 {
+        self.mmfar
+    }
+
+    /// Returns the BusFault Address Register.
+    ///
+    /// Only meaningful if `CFSR.BFARVALID` is set.
+    #[inline]
+    pub fn bfar(&self) -> u32 {
+        self.bfar
+    }
+
+    /// Returns the Auxiliary Fault Status Register.
+    ///
+    /// Always `0` on ARMv6-M and ARMv8-M Baseline.
+    #[inline]
+    pub fn afsr(&self) -> u32 {
+        self.afsr
+    }
+
+    /// Returns the stack words found above the exception frame, starting right after it.
+    ///
+    /// At most [`STACK_DUMP_WORDS`] words are saved, and the dump stops at the top of the stack
+    /// (`_stack_start`) or at the end of `RAM`.
+    #[inline]
+    pub fn stack(&self) -> &[u32] {
+        let len = (self.stack_len as usize).min(STACK_DUMP_WORDS);
+        &self.stack[..len]
+    }
+
+    /// Returns a copy of the saved crash record, if there is a valid one.
+    #[inline]
+    pub fn peek() -> Option<CrashRecord> {
+        // NOTE(unsafe) volatile reads of plain words; the contents are validated before use
+        unsafe {
+            let stored = ptr::addr_of!(CRASH_RECORD) as *const Stored;
+            if ptr::read_volatile(ptr::addr_of!((*stored).magic)) != MAGIC {
+                return None;
+            }
+            let record = ptr::read_volatile(ptr::addr_of!((*stored).record));
+            if ptr::read_volatile(ptr::addr_of!((*stored).checksum)) != checksum(&record) {
+                return None;
+            }
+            Some(record)
+        }
+    }
+
+    /// Discards the saved crash record, if any.
+    #[inline]
+    pub fn clear() {
+        // NOTE(unsafe) single volatile write to a word only used by this module
+        unsafe {
+            let stored = ptr::addr_of_mut!(CRASH_RECORD) as *mut Stored;
+            ptr::write_volatile(ptr::addr_of_mut!((*stored).magic), 0);
+        }
+    }
+
+    /// Returns the saved crash record, if there is a valid one, and clears it.
+    #[inline]
+    pub fn take() -> Option<CrashRecord> {
+        let record = Self::peek();
+        Self::clear();
+        record
+    }
+}
+
+fn checksum(record: &CrashRecord) -> u32 {
+    let words = mem::size_of::<CrashRecord>() / 4;
+    let base = record as *const CrashRecord as *const u32;
+    (0..words).fold(MAGIC, |acc, i| {
+        // NOTE(unsafe) `CrashRecord` is `repr(C)` and only made of `u32`s
+        acc.rotate_left(5) ^ unsafe { *base.add(i) }
+    })
+}
+
+#[cfg(cortex_m)]
+core::arch::global_asm!(
+    ".cfi_sections .debug_frame
+     .section .HardFault.default, \"ax\"
+     .global HardFault_
+     .type HardFault_,%function
+     .thumb_func
+     .cfi_startproc
+     HardFault_:",
+    // Pass EXC_RETURN in r0 and the stack pointer the frame was pushed on in r1.
+    "mov r0, lr
+     movs r1, #4
+     tst r0, r1
+     bne 0f
+     mrs r1, MSP
+     bl __cortex_m_rt_crash_record
+     0:
+     mrs r1, PSP
+     bl __cortex_m_rt_crash_record",
+    ".cfi_endproc
+     .size HardFault_, . - HardFault_",
+);
+
+#[cfg(cortex_m)]
+#[doc(hidden)]
+#[no_mangle]
+unsafe extern "C" fn __cortex_m_rt_crash_record(exc_return: u32, sp: *const u32) -> ! {
+    const SCB_ICSR: *const u32 = 0xE000_ED04 as *const u32;
+    const SCB_AIRCR: *mut u32 = 0xE000_ED0C as *mut u32;
+    const SCB_AIRCR_VECTKEY: u32 = 0x05FA << 16;
+    const SCB_AIRCR_PRIGROUP_MASK: u32 = 0x7 << 8;
+    const SCB_AIRCR_SYSRESETREQ: u32 = 1 << 2;
+
+    extern "C" {
+        static _stack_start: u32;
+        static _stack_end: u32;
+        static _ram_start: u32;
+        static _ram_end: u32;
+    }
+
+    let sp_addr = sp as usize;
+
+    // Only read memory that is known to exist, so the handler cannot fault itself: the stack
+    // pointer may be corrupt, for example after a stacking fault.
+    let stack_start = ptr::addr_of!(_stack_start) as usize;
+    let stack_end = ptr::addr_of!(_stack_end) as usize;
+    let ram_start = ptr::addr_of!(_ram_start) as usize;
+    let ram_end = ptr::addr_of!(_ram_end) as usize;
+    let limit = if sp_addr % 4 != 0 {
+        sp_addr
+    } else if (stack_end..stack_start).contains(&sp_addr) {
+        stack_start
+    } else if (ram_start..ram_end).contains(&sp_addr) {
+        ram_end
+    } else {
+        sp_addr
+    };
+
+    let dump = sp_addr.saturating_add(mem::size_of::<ExceptionFrame>());
+    let frame = if dump <= limit {
+        ptr::read_volatile(sp as *const ExceptionFrame)
+    } else {
+        // NOTE(unsafe) the frame is only made of words
+        mem::zeroed()
+    };
+
+    let dump = dump as *const u32;
+    let stack_len = (limit.saturating_sub(dump as usize) / 4).min(STACK_DUMP_WORDS);
+    let mut stack = [0; STACK_DUMP_WORDS];
+    for (i, word) in stack.iter_mut().enumerate().take(stack_len) {
+        *word = ptr::read_volatile(dump.add(i));
+    }
+
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    let [cfsr, hfsr, dfsr, mmfar, bfar, afsr] = [
+        0xE000_ED28_usize,
+        0xE000_ED2C,
+        0xE000_ED30,
+        0xE000_ED34,
+        0xE000_ED38,
+        0xE000_ED3C,
+    ]
+    .map(|address| ptr::read_volatile(address as *const u32));
+    #[cfg(any(armv6m, armv8m_base))]
+    let [cfsr, hfsr, dfsr, mmfar, bfar, afsr] = [0; 6];
+
+    let record = CrashRecord {
+        frame,
+        exc_return,
+        sp: sp_addr as u32,
+        vector: ptr::read_volatile(SCB_ICSR) & 0x1FF,
+        cfsr,
+        hfsr,
+        dfsr,
+        mmfar,
+        bfar,
+        afsr,
+        stack_len: stack_len as u32,
+        stack,
+    };
+
+    let stored = ptr::addr_of_mut!(CRASH_RECORD) as *mut Stored;
+    ptr::write_volatile(ptr::addr_of_mut!((*stored).record), record);
+    ptr::write_volatile(ptr::addr_of_mut!((*stored).checksum), checksum(&record));
+    ptr::write_volatile(ptr::addr_of_mut!((*stored).magic), MAGIC);
+
+    // Make sure the record reached RAM before requesting the reset.
+    core::arch::asm!("dsb", options(nostack, preserves_flags));
+    let aircr = ptr::read_volatile(SCB_AIRCR) & SCB_AIRCR_PRIGROUP_MASK;
+    ptr::write_volatile(SCB_AIRCR, SCB_AIRCR_VECTKEY | aircr | SCB_AIRCR_SYSRESETREQ);
+    core::arch::asm!("dsb", options(nostack, preserves_flags));
+
+    loop {
+        // wait for the reset to take effect
+        core::hint::spin_loop();
+    }
+}
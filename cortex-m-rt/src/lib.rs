@@ -221,6 +221,33 @@
 //! where the stack has been used the 'paint' will have been 'scrubbed off' and the memory will
 //! have a value other than `STACK_PAINT_VALUE`.
 //!
+//! ## `stack-guard`
+//!
+//! Reserves a guard region between the statically allocated RAM and the stack, and makes it
+//! inaccessible with the MPU in the reset handler, so that a stack overflow raises a MemManage
+//! fault (or a HardFault if MemManage is not enabled) instead of silently corrupting the static
+//! variables. Unlike `set-msplim`, this works on ARMv7-M and on ARMv6-M cores with an MPU. If the
+//! MPU is not implemented, the region is reserved but not protected.
+//!
+//! The guard is `_stack_guard_size` bytes long, 256 by default, aligned to its size. It can be
+//! set in `memory.x` to a power of two of at least 32 bytes (256 bytes on ARMv6-M):
+//!
+//! ```text
+//! _stack_guard_size = 1K;
+//! ```
+//!
+//! The guard uses the highest MPU region, and the MPU is enabled with the default memory map as
+//! background region for privileged accesses (`PRIVDEFENA`), so the rest of the program runs as
+//! without an MPU. The guard sits right below `_stack_end`, which therefore cannot be overridden.
+//! The heap starting at `__sheap`, if any, shares the memory above the guard with the stack.
+//!
+//! On ARMv8-M, which has no access permission that denies privileged reads, the region is
+//! privileged read-only: pushing onto the stack faults, but reading from the guard does not. The
+//! memory attribute at index 0 of MAIR0 is set to Normal memory, non-cacheable.
+//!
+//! The fault handlers run on the main stack, so the `HardFault` handler (which runs with the MPU
+//! disabled, `HFNMIENA` being cleared) writes its exception frame into the guard region.
+//!
 //! ## `skip-data-copy`
 //!
 //! Skips copying the .data section (containing the initial values for static variables) when a bootloader
@@ -229,6 +256,15 @@
 //! rp2040-boot2 with `BOOT_LOADER_RAM_MEMCPY` (not the default of boot2!) set, which copies the code out
 //! of the XIP flash memory and then disables the XIP peripheral afterwards.
 //!
+//! ## `crash-record`
+//!
+//! Replaces the default `HardFault` handler with one that saves a
+//! [`CrashRecord`](crash_record::CrashRecord) in the `.uninit` section and then resets the
+//! device. The record holds the stacked registers, `EXC_RETURN`, the fault status registers, the
+//! active vector and a bounded dump of the stack; it survives the reset and can be retrieved with
+//! [`CrashRecord::take`](crash_record::CrashRecord::take). See the [`crash_record`] module. This
+//! has no effect if the application defines its own `HardFault` handler.
+//!
 //! # Inspection
 //!
 //! This section covers how to inspect a binary that builds on top of `cortex-m-rt`.
@@ -273,7 +309,8 @@
 //!
 //! - `HardFault` and `_HardFault`. These function handle the hard fault handling and what they
 //!   do depends on whether the hard fault is overridden and whether the trampoline is enabled (which it is by default).
-//!   - No override: Both are the same function. The function is an infinite loop defined in the cortex-m-rt crate.
+//!   - No override: Both are the same function. The function is an infinite loop defined in the cortex-m-rt crate, or
+//!     the crash record handler if the `crash-record` feature is enabled.
 //!   - Trampoline enabled: `HardFault` is the real hard fault handler defined in assembly. This function is simply a
 //!     trampoline that jumps into the rust defined `_HardFault` function. This second function jumps to the user-defined
 //!     handler with the exception frame as parameter. This second jump is usually optimised away with inlining.
@@ -522,6 +559,10 @@ pub const STACK_PAINT_VALUE: u32 = 0xcccc_cccc;
 use core::arch::global_asm;
 use core::fmt;
 
+#[cfg(feature = "crash-record")]
+pub mod crash_record;
+pub mod svc;
+
 /// Parse cfg attributes inside a global_asm call.
 #[cfg(cortex_m)]
 macro_rules! cfg_global_asm {
@@ -636,6 +677,58 @@ cfg_global_asm! {
      b 0b
      1:",
 
+    // If enabled, make the stack guard region inaccessible with the highest MPU region, which
+    // takes precedence over the other regions on ARMv6-M and ARMv7-M, and enable the MPU with
+    // the default memory map as background region for privileged accesses. This is skipped if
+    // the MPU is not implemented. MPU_TYPE is 0xE000_ED90, `__sstack_guard` and the register
+    // values come from the linker script.
+    #[cfg(all(feature = "stack-guard", not(armv8m)))]
+    "ldr r0, =0xE000ED90
+     ldr r1, [r0]
+     lsrs r1, r1, #8
+     uxtb r1, r1
+     cmp r1, #0
+     beq 1f
+     subs r1, #1
+     str r1, [r0, #8]
+     ldr r1, =__sstack_guard
+     str r1, [r0, #12]
+     ldr r1, =__stack_guard_rasr
+     str r1, [r0, #16]
+     movs r1, #5
+     str r1, [r0, #4]
+     dsb
+     isb
+     1:",
+
+    // ARMv8-M has no access permission denying privileged reads, so the region is privileged
+    // read-only. Its memory attribute, index 0 in MAIR0 at 0xE000_EDC0, is set to Normal memory,
+    // non-cacheable.
+    #[cfg(all(feature = "stack-guard", armv8m))]
+    "ldr r0, =0xE000ED90
+     ldr r1, [r0]
+     lsrs r1, r1, #8
+     uxtb r1, r1
+     cmp r1, #0
+     beq 1f
+     subs r1, #1
+     str r1, [r0, #8]
+     ldr r1, [r0, #0x30]
+     movs r2, #0xFF
+     bics r1, r2
+     movs r2, #0x44
+     orrs r1, r2
+     str r1, [r0, #0x30]
+     ldr r1, =__stack_guard_rbar
+     str r1, [r0, #12]
+     ldr r1, =__stack_guard_rlar
+     str r1, [r0, #16]
+     movs r1, #5
+     str r1, [r0, #4]
+     dsb
+     isb
+     1:",
+
     // Potentially enable an FPU.
     // SCB.CPACR is 0xE000_ED88.
     // We enable access to CP10 and CP11 from priviliged and unprivileged mode.
@@ -815,7 +908,9 @@ pub use macros::entry;
 /// ## HardFault handler
 ///
 /// `#[exception(trampoline = true)] unsafe fn HardFault(..` sets the hard fault handler.
-/// If the trampoline parameter is set to true, the handler must have signature `unsafe fn(&ExceptionFrame) -> !`.
+/// If the trampoline parameter is set to true, the handler must have signature `unsafe fn(&ExceptionFrame) -> !`
+/// or `unsafe fn(&ExtendedExceptionFrame) -> !`. The latter also receives the decoded `EXC_RETURN` value and
+/// the floating-point and additional state contexts, when they were stacked.
 /// If set to false, the handler must have signature `unsafe fn() -> !`.
 ///
 /// This handler is not allowed to return as that can cause undefined behavior.
@@ -823,6 +918,14 @@ pub use macros::entry;
 /// To maintain backwards compatibility the attribute can be used without trampoline parameter (`#[exception]`),
 /// which sets the trampoline to true.
 ///
+/// ## SVCall handler
+///
+/// `#[exception] fn SVCall(..` may also take the exception frame of the code that issued the
+/// `SVC` instruction, with signature `[unsafe] fn(&mut ExceptionFrame)`. Like the `HardFault`
+/// trampoline, the stack pointer is taken from either MSP or PSP, depending on the stack in use
+/// at the time of the exception. Changes made to the frame, e.g. with `set_r0`, are unstacked on
+/// return; see the [`svc`] module for a syscall table built on this.
+///
 /// ## Default handler
 ///
 /// `#[exception] unsafe fn DefaultHandler(..` sets the *default* handler. All exceptions which have
@@ -1115,6 +1218,319 @@ impl fmt::Debug for ExceptionFrame {
     }
 }
 
+/// Stack pointer selected by an [`ExcReturn`] value
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StackPointer {
+    /// Main Stack Pointer
+    Msp,
+    /// Process Stack Pointer
+    Psp,
+}
+
+/// Processor mode selected by an [`ExcReturn`] value
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Handler mode
+    Handler,
+    /// Thread mode
+    Thread,
+}
+
+/// `EXC_RETURN` value, loaded into the Link Register on exception entry.
+///
+/// The security related fields are only meaningful on ARMv8-M with the Security Extension; on
+/// other architectures the corresponding bits are always set.
+///
+/// # Example
+///
+/// ```
+/// use cortex_m_rt::{ExcReturn, Mode, StackPointer};
+///
+/// // Return to Thread mode, using the PSP and the basic frame.
+/// let exc_return = ExcReturn::from_bits(0xFFFF_FFFD);
+/// assert_eq!(exc_return.stack_pointer(), StackPointer::Psp);
+/// assert_eq!(exc_return.mode(), Mode::Thread);
+/// assert!(!exc_return.has_fp_context());
+///
+/// // Return to Handler mode, with a floating-point context on the MSP.
+/// let exc_return = ExcReturn::from_bits(0xFFFF_FFE1);
+/// assert_eq!(exc_return.stack_pointer(), StackPointer::Msp);
+/// assert_eq!(exc_return.mode(), Mode::Handler);
+/// assert!(exc_return.has_fp_context());
+/// assert!(!exc_return.has_additional_context());
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct ExcReturn {
+    bits: u32,
+}
+
+impl ExcReturn {
+    /// Creates an `ExcReturn` value from raw bits.
+    #[inline(always)]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the raw bits of the value.
+    #[inline(always)]
+    pub const fn bits(self) -> u32 {
+        self.bits
+    }
+
+    /// Returns the stack pointer the exception frame was pushed on (`SPSEL`).
+    #[inline(always)]
+    pub const fn stack_pointer(self) -> StackPointer {
+        if self.bits & (1 << 2) != 0 {
+            StackPointer::Psp
+        } else {
+            StackPointer::Msp
+        }
+    }
+
+    /// Returns the mode the exception was taken from (`Mode`).
+    #[inline(always)]
+    pub const fn mode(self) -> Mode {
+        if self.bits & (1 << 3) != 0 {
+            Mode::Thread
+        } else {
+            Mode::Handler
+        }
+    }
+
+    /// Returns `true` if the exception frame includes the floating-point context (`FType` is
+    /// clear).
+    #[inline(always)]
+    pub const fn has_fp_context(self) -> bool {
+        self.bits & (1 << 4) == 0
+    }
+
+    /// Returns `true` if the callee-saved registers were stacked as part of the additional state
+    /// context (`DCRS` is clear).
+    #[inline(always)]
+    pub const fn has_additional_context(self) -> bool {
+        self.bits & (1 << 5) == 0
+    }
+
+    /// Returns `true` if the exception frame was pushed on a Secure stack (`S`).
+    #[inline(always)]
+    pub const fn is_secure_stack(self) -> bool {
+        self.bits & (1 << 6) != 0
+    }
+
+    /// Returns `true` if the exception was taken to the Secure state (`ES`).
+    #[inline(always)]
+    pub const fn is_secure_exception(self) -> bool {
+        self.bits & (1 << 0) != 0
+    }
+}
+
+impl fmt::Debug for ExcReturn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExcReturn")
+            .field("bits", &format_args!("0x{:08x}", self.bits))
+            .field("stack_pointer", &self.stack_pointer())
+            .field("mode", &self.mode())
+            .field("fp_context", &self.has_fp_context())
+            .field("additional_context", &self.has_additional_context())
+            .field("secure_stack", &self.is_secure_stack())
+            .field("secure_exception", &self.is_secure_exception())
+            .finish()
+    }
+}
+
+/// Floating-point registers stacked during an exception, right after the [`ExceptionFrame`].
+///
+/// Only the caller-saved registers `S0` to `S15` are part of this context. The callee-saved
+/// registers `S16` to `S31` are not stacked on ARMv7-M, and on ARMv8-M without the Security
+/// Extension: they still hold the values of the preempted code when the handler is entered, until
+/// floating-point code of the handler saves and reuses them. On ARMv8-M, when `FPCCR.TS` is set
+/// and the floating-point context belongs to the Secure state, the processor also stacks `S16` to
+/// `S31` after this context; they are not exposed here.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct FpContext {
+    s: [u32; 16],
+    fpscr: u32,
+    reserved: u32,
+}
+
+impl FpContext {
+    /// Returns the raw values of the single precision registers `S0` to `S15`.
+    #[inline(always)]
+    pub fn s(&self) -> &[u32; 16] {
+        &self.s
+    }
+
+    /// Returns the value of the Floating-Point Status and Control Register.
+    #[inline(always)]
+    pub fn fpscr(&self) -> u32 {
+        self.fpscr
+    }
+}
+
+/// Additional state context stacked before the [`ExceptionFrame`] on ARMv8-M, when a Secure
+/// context is interrupted by a Non-secure exception.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct AdditionalContext {
+    integrity_signature: u32,
+    reserved: u32,
+    r: [u32; 8],
+}
+
+impl AdditionalContext {
+    /// Returns the integrity signature.
+    #[inline(always)]
+    pub fn integrity_signature(&self) -> u32 {
+        self.integrity_signature
+    }
+
+    /// Returns the values of the callee-saved registers `R4` to `R11`.
+    #[inline(always)]
+    pub fn r4_r11(&self) -> &[u32; 8] {
+        &self.r
+    }
+}
+
+/// Exception frame including the optional floating-point and additional state contexts.
+///
+/// The `HardFault` handler receives this frame if it is declared as
+/// `#[exception] unsafe fn HardFault(ef: &ExtendedExceptionFrame) -> !`.
+#[derive(Clone, Copy, Debug)]
+pub struct ExtendedExceptionFrame {
+    exc_return: ExcReturn,
+    sp: u32,
+    additional: Option<AdditionalContext>,
+    frame: ExceptionFrame,
+    fp: Option<FpContext>,
+}
+
+impl ExtendedExceptionFrame {
+    /// Reads the exception frame pushed at `sp`, whose layout is described by `exc_return`.
+    ///
+    /// With lazy floating-point stacking the space for the floating-point context is reserved but
+    /// only filled in once the handler executes a floating-point instruction; this function
+    /// executes one before reading the context.
+    ///
+    /// # Safety
+    ///
+    /// `sp` must point to the exception frame of the exception that loaded `exc_return` into the
+    /// Link Register.
+    #[inline]
+    pub unsafe fn from_raw(sp: *const u32, exc_return: ExcReturn) -> Self {
+        let mut ptr = sp;
+
+        let additional = if exc_return.has_additional_context() {
+            let additional = core::ptr::read_volatile(ptr as *const AdditionalContext);
+            ptr = ptr.add(core::mem::size_of::<AdditionalContext>() / 4);
+            Some(additional)
+        } else {
+            None
+        };
+
+        let frame = core::ptr::read_volatile(ptr as *const ExceptionFrame);
+        ptr = ptr.add(core::mem::size_of::<ExceptionFrame>() / 4);
+
+        let fp = if exc_return.has_fp_context() {
+            // Reading FPSCR triggers the lazy state preservation, if it is still pending.
+            #[cfg(has_fpu)]
+            core::arch::asm!("vmrs {}, fpscr", out(reg) _, options(nomem, nostack, preserves_flags));
+            Some(core::ptr::read_volatile(ptr as *const FpContext))
+        } else {
+            None
+        };
+
+        ExtendedExceptionFrame {
+            exc_return,
+            sp: sp as u32,
+            additional,
+            frame,
+            fp,
+        }
+    }
+
+    /// Returns the `EXC_RETURN` value the exception was entered with.
+    #[inline(always)]
+    pub fn exc_return(&self) -> ExcReturn {
+        self.exc_return
+    }
+
+    /// Returns the registers of the basic exception frame.
+    #[inline(always)]
+    pub fn frame(&self) -> &ExceptionFrame {
+        &self.frame
+    }
+
+    /// Returns the stacked floating-point registers, if the frame includes them.
+    #[inline(always)]
+    pub fn fp_context(&self) -> Option<&FpContext> {
+        self.fp.as_ref()
+    }
+
+    /// Returns the stacked additional state context, if the frame includes it.
+    #[inline(always)]
+    pub fn additional_context(&self) -> Option<&AdditionalContext> {
+        self.additional.as_ref()
+    }
+
+    /// Returns the address the exception frame was pushed at.
+    #[inline(always)]
+    pub fn stack_pointer(&self) -> u32 {
+        self.sp
+    }
+
+    /// Returns the value of the stack pointer before the exception was taken.
+    ///
+    /// This accounts for the optional contexts and for the padding word inserted to keep the
+    /// frame 8-byte aligned, signalled by bit 9 of the stacked xPSR. It does not account for the
+    /// `S16` to `S31` registers stacked on ARMv8-M when `FPCCR.TS` is set (see [`FpContext`]).
+    #[inline]
+    pub fn preempted_stack_pointer(&self) -> u32 {
+        let mut size = core::mem::size_of::<ExceptionFrame>();
+        if self.additional.is_some() {
+            size += core::mem::size_of::<AdditionalContext>();
+        }
+        if self.fp.is_some() {
+            size += core::mem::size_of::<FpContext>();
+        }
+        if self.frame.xpsr & (1 << 9) != 0 {
+            size += 4;
+        }
+        self.sp + size as u32
+    }
+}
+
+/// Frame types a `HardFault` handler can take, when the `trampoline` is enabled.
+///
+/// The `#[exception]` attribute builds the argument of the handler with the implementation of its
+/// type, so that type aliases and re-exports of the frame types select the right layout.
+#[doc(hidden)]
+pub trait HardFaultFrame {
+    /// Calls `handler` with the frame pushed at `sp`, whose layout is described by `exc_return`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the `HardFault` trampoline.
+    unsafe fn call(sp: *const u32, exc_return: u32, handler: unsafe fn(&Self) -> !) -> !;
+}
+
+impl HardFaultFrame for ExceptionFrame {
+    #[inline(always)]
+    unsafe fn call(sp: *const u32, _exc_return: u32, handler: unsafe fn(&Self) -> !) -> ! {
+        handler(&*(sp as *const ExceptionFrame))
+    }
+}
+
+impl HardFaultFrame for ExtendedExceptionFrame {
+    #[inline(always)]
+    unsafe fn call(sp: *const u32, exc_return: u32, handler: unsafe fn(&Self) -> !) -> ! {
+        handler(&ExtendedExceptionFrame::from_raw(
+            sp,
+            ExcReturn::from_bits(exc_return),
+        ))
+    }
+}
+
 /// Returns a pointer to the start of the heap
 ///
 /// The returned pointer is guaranteed to be 4-byte aligned.
@@ -1136,6 +1552,8 @@ pub fn heap_start() -> *mut u32 {
 #[no_mangle]
 pub static __RESET_VECTOR: unsafe extern "C" fn() -> ! = Reset;
 
+// With the `crash-record` feature this handler is defined in assembly by the `crash_record` module.
+#[cfg(not(all(cortex_m, feature = "crash-record")))]
 #[doc(hidden)]
 #[cfg_attr(cortex_m, link_section = ".HardFault.default")]
 #[no_mangle]
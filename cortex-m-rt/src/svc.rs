@@ -0,0 +1,186 @@
+//! Supervisor calls
+//!
+//! Unprivileged code, such as code started with `cortex_m::psp::switch_to_unprivileged_psp`, can
+//! not perform privileged operations like masking interrupts or configuring the MPU. It can ask
+//! privileged code to perform them with the `SVC` instruction, which raises the `SVCall`
+//! exception.
+//!
+//! The [`svc!`](crate::svc!) macro issues `SVC #N` with up to four arguments in `r0`-`r3`, and
+//! returns the value the handler left in `r0`. On the handler side, an `SVCall` handler that takes
+//! the [`ExceptionFrame`] of the caller decodes `N` with [`immediate`], or forwards the call to a
+//! [`SyscallTable`], which reads the arguments from the stacked `r0`-`r3` and writes the return
+//! value to the stacked `r0`.
+//!
+//! # Example
+//!
+//! ``` ignore
+//! use cortex_m_rt::svc::SyscallTable;
+//! use cortex_m_rt::{exception, svc, ExceptionFrame};
+//!
+//! const SYS_MASK: u8 = 0;
+//! const SYS_ADD: u8 = 1;
+//!
+//! fn mask(_: [u32; 4]) -> u32 {
+//!     cortex_m::interrupt::disable();
+//!     0
+//! }
+//!
+//! fn add([a, b, _, _]: [u32; 4]) -> u32 {
+//!     a.wrapping_add(b)
+//! }
+//!
+//! static SYSCALLS: SyscallTable<2> = SyscallTable::new()
+//!     .with(SYS_MASK, mask)
+//!     .with(SYS_ADD, add);
+//!
+//! #[exception]
+//! unsafe fn SVCall(frame: &mut ExceptionFrame) {
+//!     if SYSCALLS.dispatch(frame).is_err() {
+//!         // unknown syscall
+//!     }
+//! }
+//!
+//! // from unprivileged code; the immediate must be a literal
+//! svc!(0);
+//! let three = svc!(1, 1, 2);
+//! ```
+
+use core::ptr;
+
+use crate::ExceptionFrame;
+
+/// A syscall: takes the stacked `r0`-`r3` of the caller and returns the value of its `r0`
+pub type Syscall = fn(args: [u32; 4]) -> u32;
+
+/// The immediate of an `SVC` instruction with no entry in a [`SyscallTable`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnknownSyscall(pub u8);
+
+/// A table of up to `N` syscalls, indexed by the immediate of the `SVC` instruction
+#[derive(Clone, Copy)]
+pub struct SyscallTable<const N: usize> {
+    syscalls: [Option<Syscall>; N],
+}
+
+impl<const N: usize> SyscallTable<N> {
+    /// Creates an empty table.
+    #[inline]
+    pub const fn new() -> Self {
+        const { assert!(N <= 256, "an SVC immediate can only index 256 syscalls") };
+
+        SyscallTable {
+            syscalls: [None; N],
+        }
+    }
+
+    /// Adds `syscall` to the table, as syscall `number`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `number` does not fit in the table, or if it is already taken. When the table
+    /// is built in a `const` or a `static`, this is a compile-time error.
+    #[inline]
+    pub const fn with(mut self, number: u8, syscall: Syscall) -> Self {
+        let index = number as usize;
+        assert!(index < N, "the syscall number does not fit in the table");
+        assert!(
+            self.syscalls[index].is_none(),
+            "the syscall number is already taken"
+        );
+        self.syscalls[index] = Some(syscall);
+        self
+    }
+
+    /// Returns syscall `number`, if any.
+    #[inline]
+    pub fn get(&self, number: u8) -> Option<Syscall> {
+        self.syscalls.get(usize::from(number)).copied().flatten()
+    }
+
+    /// Calls the syscall raised by the `SVC` instruction of `frame`, and writes its return value
+    /// to the stacked `r0`.
+    ///
+    /// Returns the syscall number, or [`UnknownSyscall`] if the table has no such syscall, in
+    /// which case `frame` is left untouched.
+    ///
+    /// # Safety
+    ///
+    /// See [`immediate`]. The caller of `SVC` must expect `r0` to be overwritten, as the
+    /// [`svc!`](crate::svc!) macro does.
+    #[inline]
+    pub unsafe fn dispatch(&self, frame: &mut ExceptionFrame) -> Result<u8, UnknownSyscall> {
+        let number = immediate(frame);
+        let syscall = self.get(number).ok_or(UnknownSyscall(number))?;
+        let value = syscall([frame.r0(), frame.r1(), frame.r2(), frame.r3()]);
+        frame.set_r0(value);
+        Ok(number)
+    }
+}
+
+impl<const N: usize> Default for SyscallTable<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the immediate of the `SVC` instruction that raised the `SVCall` exception of `frame`.
+///
+/// The stacked program counter is the address of the instruction following `SVC`, so the
+/// immediate is the low byte of the previous halfword.
+///
+/// # Safety
+///
+/// `frame` must be the exception frame stacked on entry to the `SVCall` exception, as passed to
+/// an `SVCall` handler taking an `&mut ExceptionFrame`. The code of the caller must be readable.
+#[inline]
+pub unsafe fn immediate(frame: &ExceptionFrame) -> u8 {
+    let svc = ptr::read_volatile((frame.pc() as *const u16).sub(1));
+    svc as u8
+}
+
+/// Issues a supervisor call: `SVC #N` with up to four `u32` arguments in `r0`-`r3`.
+///
+/// Returns the value of `r0` after the call, which is the return value of the syscall when the
+/// `SVCall` handler uses [`SyscallTable::dispatch`](crate::svc::SyscallTable::dispatch). The syscall
+/// number must be a literal, as it is encoded in the instruction.
+///
+/// ``` ignore
+/// use cortex_m_rt::svc;
+///
+/// let value: u32 = svc!(3, 0x2000_0000, 4);
+/// ```
+#[macro_export]
+macro_rules! svc {
+    ($n:literal $(,)?) => {
+        $crate::svc!($n, 0, 0, 0, 0)
+    };
+    ($n:literal, $a0:expr $(,)?) => {
+        $crate::svc!($n, $a0, 0, 0, 0)
+    };
+    ($n:literal, $a0:expr, $a1:expr $(,)?) => {
+        $crate::svc!($n, $a0, $a1, 0, 0)
+    };
+    ($n:literal, $a0:expr, $a1:expr, $a2:expr $(,)?) => {
+        $crate::svc!($n, $a0, $a1, $a2, 0)
+    };
+    ($n:literal, $a0:expr, $a1:expr, $a2:expr, $a3:expr $(,)?) => {{
+        let r0: u32 = $a0;
+        let r1: u32 = $a1;
+        let r2: u32 = $a2;
+        let r3: u32 = $a3;
+        let value: u32;
+        // NOTE(unsafe) the `SVCall` handler only changes the stacked `r0`
+        unsafe {
+            ::core::arch::asm!(
+                ::core::concat!("svc #", ::core::stringify!($n)),
+                inlateout("r0") r0 => value,
+                in("r1") r1,
+                in("r2") r2,
+                in("r3") r3,
+                options(nostack),
+            );
+        }
+        value
+    }};
+}
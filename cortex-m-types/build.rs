@@ -0,0 +1,13 @@
+use std::env;
+
+fn main() {
+    let target = env::var("TARGET").unwrap();
+
+    println!("cargo:rustc-check-cfg=cfg(armv6m)");
+
+    // The NVIC of ARMv6-M only supports 32 interrupts, which bounds the interrupt numbers of the
+    // `bare_metal::Nr` compatibility implementation
+    if target.starts_with("thumbv6m-") {
+        println!("cargo:rustc-cfg=armv6m");
+    }
+}
@@ -0,0 +1,247 @@
+//! Derive macros for the traits of `cortex-m-types`.
+//!
+//! These macros are re-exported by `cortex-m-types` when its `derive` feature is enabled. They
+//! implement [`ExceptionNumber`], [`InterruptNumber`], [`PriorityNumber`] and [`CoreIdNumber`] on
+//! enums of unit variants:
+//!
+//! - `number` returns the discriminant of the variant.
+//! - `from_number` matches every discriminant and returns `Error::InvalidVariant` otherwise.
+//! - `MAX_*_NUMBER` is the highest discriminant of the enum.
+//!
+//! Discriminants must be integer literals. Variants without an explicit discriminant take the
+//! value of the previous variant plus one, as usual. Enums with duplicate discriminants or with
+//! variants holding data are rejected at compile time.
+//!
+//! ```
+//! use cortex_m_types::InterruptNumber;
+//! use cortex_m_types_macros::InterruptNumber;
+//!
+//! #[derive(Clone, Copy, Debug, Eq, PartialEq, InterruptNumber)]
+//! enum Interrupt {
+//!     Wwdg = 0,
+//!     Pvd,
+//!     Usart1 = 37,
+//! }
+//!
+//! assert_eq!(Interrupt::MAX_INTERRUPT_NUMBER, 37);
+//! assert_eq!(Interrupt::Pvd.number(), 1);
+//! assert_eq!(Interrupt::from_number(37), Ok(Interrupt::Usart1));
+//! assert!(Interrupt::from_number(2).is_err());
+//! ```
+//!
+//! The generated code refers to `::cortex_m_types` by default. Crates that only depend on
+//! `cortex-m`, which re-exports the traits, can change the path with the `cortex_m_types`
+//! attribute:
+//!
+//! ```ignore
+//! #[derive(Clone, Copy, cortex_m_types_macros::InterruptNumber)]
+//! #[cortex_m_types(crate = cortex_m)]
+//! enum Interrupt {
+//!     Wwdg = 0,
+//! }
+//! ```
+//!
+//! Duplicate discriminants are rejected:
+//!
+//! ```compile_fail
+//! #[derive(Clone, Copy, cortex_m_types_macros::PriorityNumber)]
+//! enum Priority {
+//!     P0 = 0,
+//!     P1 = 1,
+//!     Also1 = 1,
+//! }
+//! ```
+//!
+//! And so are variants that are not unit variants:
+//!
+//! ```compile_fail
+//! #[derive(Clone, Copy, cortex_m_types_macros::CoreIdNumber)]
+//! enum CoreId {
+//!     H0,
+//!     H1(u8),
+//! }
+//! ```
+//!
+//! [`ExceptionNumber`]: https://docs.rs/cortex-m-types/latest/cortex_m_types/trait.ExceptionNumber.html
+//! [`InterruptNumber`]: https://docs.rs/cortex-m-types/latest/cortex_m_types/trait.InterruptNumber.html
+//! [`PriorityNumber`]: https://docs.rs/cortex-m-types/latest/cortex_m_types/trait.PriorityNumber.html
+//! [`CoreIdNumber`]: https://docs.rs/cortex-m-types/latest/cortex_m_types/trait.CoreIdNumber.html
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use syn::{
+    parse::{self, Parse},
+    parse_macro_input, parse_quote,
+    spanned::Spanned,
+    Data, DeriveInput, Expr, ExprLit, Fields, Ident, Lit, Path,
+};
+
+/// Derives `cortex_m_types::ExceptionNumber` for an enum of exceptions.
+#[proc_macro_derive(ExceptionNumber, attributes(cortex_m_types))]
+pub fn exception_number(input: TokenStream) -> TokenStream {
+    derive_number(input, "ExceptionNumber", "MAX_EXCEPTION_NUMBER")
+}
+
+/// Derives `cortex_m_types::InterruptNumber` for an enum of interrupts.
+#[proc_macro_derive(InterruptNumber, attributes(cortex_m_types))]
+pub fn interrupt_number(input: TokenStream) -> TokenStream {
+    derive_number(input, "InterruptNumber", "MAX_INTERRUPT_NUMBER")
+}
+
+/// Derives `cortex_m_types::PriorityNumber` for an enum of priority levels.
+#[proc_macro_derive(PriorityNumber, attributes(cortex_m_types))]
+pub fn priority_number(input: TokenStream) -> TokenStream {
+    derive_number(input, "PriorityNumber", "MAX_PRIORITY_NUMBER")
+}
+
+/// Derives `cortex_m_types::CoreIdNumber` for an enum of core identifiers.
+#[proc_macro_derive(CoreIdNumber, attributes(cortex_m_types))]
+pub fn core_id_number(input: TokenStream) -> TokenStream {
+    derive_number(input, "CoreIdNumber", "MAX_CORE_ID_NUMBER")
+}
+
+fn derive_number(input: TokenStream, trait_name: &str, max_const: &str) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input, trait_name, max_const) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput, trait_name: &str, max_const: &str) -> parse::Result<TokenStream2> {
+    let ident = &input.ident;
+    let krate = crate_path(input)?;
+
+    if !input.generics.params.is_empty() {
+        return Err(parse::Error::new(
+            input.generics.span(),
+            format!("`#[derive({trait_name})]` does not support generic enums"),
+        ));
+    }
+
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(parse::Error::new(
+                ident.span(),
+                format!("`#[derive({trait_name})]` can only be applied to enums"),
+            ))
+        }
+    };
+
+    if data.variants.is_empty() {
+        return Err(parse::Error::new(
+            ident.span(),
+            format!("`#[derive({trait_name})]` requires at least one variant"),
+        ));
+    }
+
+    let mut seen: HashMap<usize, &Ident> = HashMap::new();
+    let mut variants = Vec::with_capacity(data.variants.len());
+    let mut next = 0usize;
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(parse::Error::new(
+                variant.span(),
+                format!("`#[derive({trait_name})]` only supports unit variants"),
+            ));
+        }
+
+        let number = match &variant.discriminant {
+            Some((_, expr)) => discriminant(expr, trait_name)?,
+            None => next,
+        };
+
+        if let Some(other) = seen.insert(number, &variant.ident) {
+            return Err(parse::Error::new(
+                variant.span(),
+                format!(
+                    "variant `{}` has the same number ({number}) as `{other}`",
+                    variant.ident
+                ),
+            ));
+        }
+
+        variants.push((&variant.ident, number));
+        next = number
+            .checked_add(1)
+            .ok_or_else(|| parse::Error::new(variant.span(), "discriminant overflows `usize`"))?;
+    }
+
+    let max = variants.iter().map(|(_, number)| *number).max().unwrap();
+    let trait_ident = format_ident!("{}", trait_name);
+    let max_ident = format_ident!("{}", max_const);
+    let arms = variants
+        .iter()
+        .map(|(variant, number)| quote!(#number => Ok(Self::#variant),));
+
+    Ok(quote! {
+        unsafe impl #krate::#trait_ident for #ident {
+            const #max_ident: usize = #max;
+
+            #[inline]
+            fn number(self) -> usize {
+                self as usize
+            }
+
+            #[inline]
+            fn from_number(number: usize) -> #krate::result::Result<Self> {
+                match number {
+                    #(#arms)*
+                    _ => Err(#krate::result::Error::InvalidVariant(number)),
+                }
+            }
+        }
+    })
+}
+
+/// Extracts the value of an integer literal discriminant.
+fn discriminant(expr: &Expr, trait_name: &str) -> parse::Result<usize> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        Expr::Group(group) => discriminant(&group.expr, trait_name),
+        Expr::Paren(paren) => discriminant(&paren.expr, trait_name),
+        _ => Err(parse::Error::new(
+            expr.span(),
+            format!("`#[derive({trait_name})]` requires integer literal discriminants"),
+        )),
+    }
+}
+
+/// Returns the path to `cortex-m-types`, as given by `#[cortex_m_types(crate = path)]`.
+fn crate_path(input: &DeriveInput) -> parse::Result<Path> {
+    let mut krate = parse_quote!(::cortex_m_types);
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cortex_m_types"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = CratePath::parse(meta.value()?)?.0;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `cortex_m_types` attribute"))
+            }
+        })?;
+    }
+
+    Ok(krate)
+}
+
+struct CratePath(Path);
+
+impl Parse for CratePath {
+    fn parse(input: parse::ParseStream) -> parse::Result<Self> {
+        Ok(CratePath(Path::parse_mod_style(input)?))
+    }
+}
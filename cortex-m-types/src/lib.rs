@@ -4,6 +4,13 @@ pub mod result;
 
 use result::Result;
 
+/// Derive macros for the traits of this crate, enabled by the `derive` feature.
+///
+/// Each macro implements its trait on an enum of unit variants with integer literal
+/// discriminants. See the `cortex-m-types-macros` crate for details.
+#[cfg(feature = "derive")]
+pub use cortex_m_types_macros::{CoreIdNumber, ExceptionNumber, InterruptNumber, PriorityNumber};
+
 /// Trait for enums of target-specific exception numbers.
 ///
 /// This trait should be implemented by a peripheral access crate (PAC) on its enum of available
@@ -53,6 +60,34 @@ pub unsafe trait InterruptNumber: Copy {
     fn from_number(value: usize) -> Result<Self>;
 }
 
+/// Compatibility implementation for interrupt enums that only implement the old
+/// `bare_metal::Nr` trait, enabled by the `bare-metal` feature.
+///
+/// As `Nr` provides no way back from a number to a variant, `from_number` always returns
+/// [`Error::Unimplemented`](result::Error::Unimplemented), and `MAX_INTERRUPT_NUMBER` is the
+/// highest interrupt number supported by the NVIC of the target architecture that fits in the
+/// `u8` returned by `Nr`.
+///
+/// This implementation is for backwards compatibility only and will be removed once PACs have
+/// moved to `InterruptNumber`.
+#[cfg(feature = "bare-metal")]
+unsafe impl<T: bare_metal::Nr + Copy> InterruptNumber for T {
+    #[cfg(armv6m)]
+    const MAX_INTERRUPT_NUMBER: usize = 31;
+    #[cfg(not(armv6m))]
+    const MAX_INTERRUPT_NUMBER: usize = u8::MAX as usize;
+
+    #[inline]
+    fn number(self) -> usize {
+        usize::from(self.nr())
+    }
+
+    #[inline]
+    fn from_number(_value: usize) -> Result<Self> {
+        Err(result::Error::Unimplemented)
+    }
+}
+
 /// Trait for enums of priority levels.
 ///
 /// This trait should be implemented by a peripheral access crate (PAC) on its enum of available
@@ -261,4 +296,26 @@ mod test {
         assert_eq!(CoreId::from_number(2), Ok(CoreId::H2));
         assert_eq!(CoreId::from_number(3), Err(Error::InvalidVariant(3)));
     }
+
+    #[cfg(feature = "bare-metal")]
+    #[test]
+    fn check_nr_enum() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        enum Legacy {
+            L7 = 7,
+        }
+
+        unsafe impl bare_metal::Nr for Legacy {
+            fn nr(&self) -> u8 {
+                *self as u8
+            }
+        }
+
+        assert_eq!(InterruptNumber::number(Legacy::L7), 7);
+        assert_eq!(Legacy::MAX_INTERRUPT_NUMBER, 255);
+        assert_eq!(
+            <Legacy as InterruptNumber>::from_number(7),
+            Err(Error::Unimplemented)
+        );
+    }
 }
@@ -27,6 +27,12 @@ pub enum Error {
     InvalidVariant(usize),
     /// Unimplemented function or type.
     Unimplemented,
+    /// Attempted to take a resource that has already been taken.
+    AlreadyTaken,
+    /// Attempted to access a core-local resource from another core.
+    WrongCore { expected: usize, current: usize },
+    /// Attempted to configure a memory region that overlaps another one.
+    RegionOverlap { region: usize, other: usize },
 }
 
 impl fmt::Display for Error {
@@ -54,6 +60,13 @@ impl fmt::Display for Error {
                 write!(f, "invalid variant: {value:#x}")
             }
             Self::Unimplemented => write!(f, "unimplemented"),
+            Self::AlreadyTaken => write!(f, "resource already taken"),
+            Self::WrongCore { expected, current } => {
+                write!(f, "wrong core, expected: {expected}, current: {current}")
+            }
+            Self::RegionOverlap { region, other } => {
+                write!(f, "region {region} overlaps region {other}")
+            }
         }
     }
 }
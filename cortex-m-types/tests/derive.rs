@@ -0,0 +1,89 @@
+use cortex_m_types::{
+    result::Error, CoreIdNumber, ExceptionNumber, InterruptNumber, PriorityNumber,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types_macros::ExceptionNumber)]
+enum Exception {
+    E1 = 1,
+    E3 = 3,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types_macros::InterruptNumber)]
+enum Interrupt {
+    I1 = 1,
+    I2,
+    I4 = 4,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types_macros::PriorityNumber)]
+enum Priority {
+    P0,
+    P1,
+    P2,
+    P3,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types_macros::CoreIdNumber)]
+enum CoreId {
+    H2 = 2,
+    H0 = 0,
+    H1,
+}
+
+mod renamed {
+    pub use cortex_m_types as types;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types_macros::InterruptNumber)]
+#[cortex_m_types(crate = renamed::types)]
+enum RenamedInterrupt {
+    Only = 7,
+}
+
+#[test]
+fn derive_exception_number() {
+    assert_eq!(Exception::MAX_EXCEPTION_NUMBER, 3);
+    assert_eq!(Exception::E1.number(), 1);
+    assert_eq!(Exception::E3.number(), 3);
+
+    assert_eq!(Exception::from_number(0), Err(Error::InvalidVariant(0)));
+    assert_eq!(Exception::from_number(1), Ok(Exception::E1));
+    assert_eq!(Exception::from_number(2), Err(Error::InvalidVariant(2)));
+    assert_eq!(Exception::from_number(3), Ok(Exception::E3));
+}
+
+#[test]
+fn derive_interrupt_number() {
+    assert_eq!(Interrupt::MAX_INTERRUPT_NUMBER, 4);
+    assert_eq!(Interrupt::I2.number(), 2);
+
+    assert_eq!(Interrupt::from_number(2), Ok(Interrupt::I2));
+    assert_eq!(Interrupt::from_number(3), Err(Error::InvalidVariant(3)));
+    assert_eq!(Interrupt::from_number(4), Ok(Interrupt::I4));
+}
+
+#[test]
+fn derive_priority_number() {
+    assert_eq!(Priority::MAX_PRIORITY_NUMBER, 3);
+    assert_eq!(Priority::P0.number(), 0);
+    assert_eq!(Priority::P3.number(), 3);
+
+    assert_eq!(Priority::from_number(1), Ok(Priority::P1));
+    assert_eq!(Priority::from_number(4), Err(Error::InvalidVariant(4)));
+}
+
+#[test]
+fn derive_core_id_number() {
+    assert_eq!(CoreId::MAX_CORE_ID_NUMBER, 2);
+    assert_eq!(CoreId::H1.number(), 1);
+
+    assert_eq!(CoreId::from_number(0), Ok(CoreId::H0));
+    assert_eq!(CoreId::from_number(2), Ok(CoreId::H2));
+    assert_eq!(CoreId::from_number(3), Err(Error::InvalidVariant(3)));
+}
+
+#[test]
+fn derive_with_crate_path() {
+    assert_eq!(RenamedInterrupt::MAX_INTERRUPT_NUMBER, 7);
+    assert_eq!(RenamedInterrupt::from_number(7), Ok(RenamedInterrupt::Only));
+}
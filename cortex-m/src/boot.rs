@@ -0,0 +1,334 @@
+//! Chain-loading of firmware images
+//!
+//! A bootloader hands over to an application by pointing VTOR to the application's vector table,
+//! loading its initial stack pointer and jumping to its reset handler. [`asm::bootload`] does the
+//! last two steps, but it is up to the caller to check the image and to leave the core in a state
+//! the application does not trip over. [`jump_to_image`] does all of it:
+//!
+//! 1. The image is validated: the vector table must be aligned, the initial stack pointer must be
+//!    inside RAM, the reset vector must be a Thumb address inside Flash and, optionally, the CRC
+//!    of the image must match.
+//! 2. The core is quiesced with [`quiesce`]: all the interrupts are disabled and their pending
+//!    state is cleared, SysTick is stopped, the MPU and the caches are disabled, and CONTROL and
+//!    PSP are reset.
+//! 3. VTOR is pointed to the image and [`asm::bootstrap`] jumps to it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cortex_m::boot::{self, ImageCrc, ImageLayout};
+//!
+//! const APP: usize = 0x0800_8000;
+//!
+//! let layout = ImageLayout {
+//!     flash: 0x0800_0000..0x0810_0000,
+//!     ram: 0x2000_0000..0x2002_0000,
+//!     crc: Some(ImageCrc {
+//!         len: 0x1_0000,
+//!         expected: 0x1234_5678,
+//!     }),
+//! };
+//!
+//! let error = unsafe { boot::jump_to_image(APP as *const u32, &layout) };
+//! // only reached if the image is not valid
+//! # let _ = error;
+//! ```
+//!
+//! [`asm::bootload`]: crate::asm::bootload
+//! [`asm::bootstrap`]: crate::asm::bootstrap
+
+use core::ops::Range;
+use core::ptr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::peripheral::{Peripherals, SCB};
+use crate::register::{control, psp};
+
+/// The low bits of VTOR that are always zero
+const VTOR_ALIGN_MASK: usize = 0x7F;
+
+/// Memory layout an image is validated against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImageLayout {
+    /// Addresses the reset vector of the image may point to.
+    pub flash: Range<usize>,
+    /// Addresses the initial stack pointer of the image may point to. As the stack is full
+    /// descending, the initial stack pointer may also be the end of the range.
+    pub ram: Range<usize>,
+    /// CRC of the image, if it must be checked.
+    pub crc: Option<ImageCrc>,
+}
+
+/// Expected CRC of an image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ImageCrc {
+    /// Length in bytes of the image, starting at its vector table.
+    pub len: usize,
+    /// Expected CRC-32 of the image, as computed by [`crc32`].
+    pub expected: u32,
+}
+
+/// Possible error values returned by the image validation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ImageError {
+    /// The vector table is not aligned on 128 bytes, so it cannot be loaded into VTOR.
+    MisalignedVectorTable(usize),
+    /// The initial stack pointer is not word aligned, or not inside RAM.
+    InvalidStackPointer(u32),
+    /// The reset vector does not have the Thumb bit set, or is not inside Flash.
+    InvalidResetVector(u32),
+    /// The CRC of the image does not match the expected one.
+    CrcMismatch {
+        /// The expected CRC.
+        expected: u32,
+        /// The CRC computed over the image.
+        computed: u32,
+    },
+}
+
+/// An image that passed validation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Image {
+    vector_table: *const u32,
+    stack_pointer: u32,
+    reset_vector: u32,
+}
+
+impl Image {
+    /// Validates the image whose vector table is at `vector_table` against `layout`.
+    ///
+    /// # Safety
+    ///
+    /// The first two words of the vector table, and the `crc.len` bytes of the image if a CRC is
+    /// given, must be readable.
+    #[inline]
+    pub unsafe fn validate(
+        vector_table: *const u32,
+        layout: &ImageLayout,
+    ) -> Result<Self, ImageError> {
+        let address = vector_table as usize;
+        if address & VTOR_ALIGN_MASK != 0 {
+            return Err(ImageError::MisalignedVectorTable(address));
+        }
+
+        let stack_pointer = ptr::read_volatile(vector_table);
+        let sp = stack_pointer as usize;
+        if sp & 0x3 != 0 || sp <= layout.ram.start || sp > layout.ram.end {
+            return Err(ImageError::InvalidStackPointer(stack_pointer));
+        }
+
+        let reset_vector = ptr::read_volatile(vector_table.add(1));
+        if reset_vector & 1 == 0 || !layout.flash.contains(&(reset_vector as usize & !1)) {
+            return Err(ImageError::InvalidResetVector(reset_vector));
+        }
+
+        if let Some(crc) = layout.crc {
+            let image = core::slice::from_raw_parts(vector_table as *const u8, crc.len);
+            let computed = crc32(image);
+            if computed != crc.expected {
+                return Err(ImageError::CrcMismatch {
+                    expected: crc.expected,
+                    computed,
+                });
+            }
+        }
+
+        Ok(Image {
+            vector_table,
+            stack_pointer,
+            reset_vector,
+        })
+    }
+
+    /// Returns the address of the vector table of the image.
+    #[inline]
+    pub fn vector_table(&self) -> *const u32 {
+        self.vector_table
+    }
+
+    /// Returns the initial stack pointer of the image.
+    #[inline]
+    pub fn stack_pointer(&self) -> u32 {
+        self.stack_pointer
+    }
+
+    /// Returns the reset vector of the image.
+    #[inline]
+    pub fn reset_vector(&self) -> u32 {
+        self.reset_vector
+    }
+
+    /// Quiesces the core, points VTOR to the image and jumps to its reset handler.
+    ///
+    /// Interrupts are enabled again right before the jump, as they are out of reset.
+    ///
+    /// # Safety
+    ///
+    /// Must be called from privileged Thread mode. See [`quiesce`]. The image must not have
+    /// changed since it was validated.
+    #[inline]
+    pub unsafe fn boot(self) -> ! {
+        let mut p = Peripherals::steal();
+        quiesce(&mut p);
+
+        p.SCB.vtor.write(self.vector_table as u32);
+        crate::asm::dsb();
+        crate::asm::isb();
+
+        crate::interrupt::enable();
+        crate::asm::bootstrap(
+            self.stack_pointer as *const u32,
+            self.reset_vector as *const u32,
+        )
+    }
+}
+
+/// Validates the image whose vector table is at `vector_table`, and boots it.
+///
+/// Only returns if the image is not valid. See [`Image::validate`] and [`Image::boot`].
+///
+/// # Safety
+///
+/// Must be called from privileged Thread mode. See [`Image::validate`] and [`quiesce`].
+#[inline]
+pub unsafe fn jump_to_image(vector_table: *const u32, layout: &ImageLayout) -> ImageError {
+    match Image::validate(vector_table, layout) {
+        Ok(image) => image.boot(),
+        Err(error) => error,
+    }
+}
+
+/// Brings the core close to its reset state before handing over to another image.
+///
+/// Interrupts are disabled with PRIMASK, and left disabled. All the NVIC interrupts are disabled
+/// and their pending state is cleared, as well as the pending state of PendSV and SysTick.
+/// SysTick is stopped, the MPU is disabled and, on the cores that have them, the D-cache is
+/// cleaned and disabled and the I-cache is disabled. BASEPRI is cleared where implemented. The
+/// privileged and FPCA bits of CONTROL are cleared and, if the main stack is in use, PSP is
+/// zeroed. The stack pointer selection is left to [`asm::bootstrap`](crate::asm::bootstrap),
+/// which switches to the main stack.
+///
+/// # Safety
+///
+/// Must be called from privileged Thread mode. Drivers relying on interrupts, on the MPU or on
+/// the process stack stop working.
+#[inline]
+pub unsafe fn quiesce(p: &mut Peripherals) {
+    crate::interrupt::disable();
+
+    for (icer, icpr) in p.NVIC.icer.iter().zip(p.NVIC.icpr.iter()) {
+        icer.write(0xFFFF_FFFF);
+        icpr.write(0xFFFF_FFFF);
+    }
+
+    p.SYST.disable_interrupt();
+    p.SYST.disable_counter();
+    p.SYST.clear_current();
+    SCB::clear_pendst();
+    SCB::clear_pendsv();
+
+    crate::asm::dsb();
+    p.MPU.ctrl.write(0);
+    crate::asm::dsb();
+    crate::asm::isb();
+
+    #[cfg(not(armv6m))]
+    {
+        p.SCB.disable_dcache(&mut p.CPUID);
+        p.SCB.disable_icache();
+    }
+
+    #[cfg(all(not(armv6m), not(armv8m_base)))]
+    crate::register::basepri::write(0);
+
+    let mut control = control::read();
+    control.set_npriv(control::Npriv::Privileged);
+    control.set_fpca(control::Fpca::NotActive);
+    control::write(control);
+    crate::asm::isb();
+    if control.spsel().is_msp() {
+        psp::write(0);
+    }
+}
+
+/// Computes the CRC-32 of `data` (the IEEE 802.3 polynomial, as used by zlib and Ethernet).
+#[inline]
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_validation() {
+        #[repr(C, align(128))]
+        struct Vectors([u32; 4]);
+
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(&[]), 0);
+
+        let mut vectors = Vectors([0x2002_0000, 0x0800_0401, 0, 0]);
+        let crc =
+            crc32(unsafe { core::slice::from_raw_parts(vectors.0.as_ptr() as *const u8, 16) });
+        let mut layout = ImageLayout {
+            flash: 0x0800_0000..0x0810_0000,
+            ram: 0x2000_0000..0x2002_0000,
+            crc: Some(ImageCrc {
+                len: 16,
+                expected: crc,
+            }),
+        };
+
+        let image = unsafe { Image::validate(vectors.0.as_ptr(), &layout) }.unwrap();
+        assert_eq!(image.stack_pointer(), 0x2002_0000);
+        assert_eq!(image.reset_vector(), 0x0800_0401);
+
+        let misaligned = unsafe { vectors.0.as_ptr().add(1) };
+        assert_eq!(
+            unsafe { Image::validate(misaligned, &layout) },
+            Err(ImageError::MisalignedVectorTable(misaligned as usize))
+        );
+
+        layout.crc = Some(ImageCrc {
+            len: 16,
+            expected: !crc,
+        });
+        assert_eq!(
+            unsafe { Image::validate(vectors.0.as_ptr(), &layout) },
+            Err(ImageError::CrcMismatch {
+                expected: !crc,
+                computed: crc
+            })
+        );
+        layout.crc = None;
+
+        for sp in [0x2002_0004, 0x2000_0000, 0x2001_0002] {
+            vectors.0[0] = sp;
+            assert_eq!(
+                unsafe { Image::validate(vectors.0.as_ptr(), &layout) },
+                Err(ImageError::InvalidStackPointer(sp))
+            );
+        }
+        vectors.0[0] = 0x2001_0000;
+
+        for reset in [0x0800_0400, 0x0000_0401, 0x0810_0001] {
+            vectors.0[1] = reset;
+            assert_eq!(
+                unsafe { Image::validate(vectors.0.as_ptr(), &layout) },
+                Err(ImageError::InvalidResetVector(reset))
+            );
+        }
+    }
+}
@@ -34,10 +34,14 @@
 
 use crate::asm::{tt, tta, ttat, ttt};
 use bitfield::bitfield;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Memory access behaviour: determine which privilege execution mode is used and which Memory
 /// Protection Unit (MPU) is used.
 #[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AccessType {
     /// Access using current privilege level and reading from current security state MPU.
     /// Uses the TT instruction.
@@ -55,6 +59,8 @@ pub enum AccessType {
 /// Abstraction of TT instructions and helper functions to determine the security and privilege
 /// attribute of a target address, accessed in different ways.
 #[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct TestTarget {
     tt_resp: TtResp,
     access_type: AccessType,
@@ -65,6 +71,8 @@ bitfield! {
     ///
     /// Provides the response payload from a TT, TTA, TTT or TTAT instruction.
     #[derive(PartialEq, Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     struct TtResp(u32);
     impl Debug;
     mregion, _: 7, 0;
@@ -0,0 +1,383 @@
+//! Software task dispatcher
+//!
+//! Device interrupts that are not used by any peripheral can run deferred work at a chosen
+//! priority. Each such interrupt is a [`Lane`]: work items are posted to the lane from any
+//! context, which pends the interrupt (through STIR, or ISPR on ARMv6-M), and the interrupt
+//! handler drains the queue of the lane at the priority of the interrupt.
+//!
+//! The queue of a lane is a bounded [`Queue`] that producers can use concurrently from any
+//! priority without masking interrupts, except on ARMv6-M, which lacks compare-and-swap and
+//! reserves a queue slot in a short critical section instead.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cortex_m::dispatcher::Lane;
+//! use cortex_m::peripheral::Peripherals;
+//! # use cortex_m::interrupt::InterruptNumber;
+//! # #[derive(Clone, Copy, Debug)]
+//! # enum Interrupt { Swi0 = 30 }
+//! # unsafe impl InterruptNumber for Interrupt {
+//! #     const MAX_INTERRUPT_NUMBER: usize = 31;
+//! #     fn number(self) -> usize { self as usize }
+//! #     fn from_number(n: usize) -> cortex_m::result::Result<Self> {
+//! #         Err(cortex_m::result::Error::InvalidVariant(n))
+//! #     }
+//! # }
+//! # #[derive(Clone, Copy)]
+//! # struct Priority(usize);
+//! # unsafe impl cortex_m::PriorityNumber for Priority {
+//! #     const MAX_PRIORITY_NUMBER: usize = 7;
+//! #     fn number(self) -> usize { self.0 }
+//! #     fn from_number(n: usize) -> cortex_m::result::Result<Self> { Ok(Priority(n)) }
+//! # }
+//!
+//! enum Work {
+//!     Flush,
+//!     Log(u32),
+//! }
+//!
+//! static LOW: Lane<Interrupt, Work, 8> = Lane::new(Interrupt::Swi0);
+//!
+//! // the handler of the `Swi0` interrupt
+//! fn swi0() {
+//!     LOW.drain(|work| match work {
+//!         Work::Flush => {}
+//!         Work::Log(_value) => {}
+//!     });
+//! }
+//!
+//! let p = Peripherals::take().unwrap();
+//! let mut handles = p.NVIC.into_handles();
+//! unsafe { LOW.start(handles.take(Interrupt::Swi0).unwrap(), Priority(1)) };
+//!
+//! // from anywhere
+//! if LOW.post(Work::Log(42)).is_err() {
+//!     // the queue is full
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::nvic::InterruptHandle;
+#[cfg(armv6m)]
+use crate::peripheral::NVIC;
+use crate::peripheral::SCB;
+use crate::priority::HwPriority;
+
+/// A bounded queue with multiple producers and a single consumer
+///
+/// `N` must be a power of two. Producers may preempt each other, and the consumer, at any point.
+/// An item whose producer was preempted while writing it is only seen by the consumer once it is
+/// fully written, together with the items posted after it.
+pub struct Queue<T, const N: usize> {
+    /// Position of the next slot to write
+    enqueue: AtomicUsize,
+    /// Position of the next slot to read, only written by the consumer
+    dequeue: AtomicUsize,
+    slots: [Slot<T>; N],
+}
+
+struct Slot<T> {
+    /// Sequence number of the slot, minus its index, so that all the slots start at zero: the
+    /// slot is free for position `pos` when the sequence is `pos`, and holds the item of `pos`
+    /// when it is `pos + 1`.
+    sequence: AtomicUsize,
+    item: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Slot<T> {
+    const fn new() -> Self {
+        Slot {
+            sequence: AtomicUsize::new(0),
+            item: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+// NOTE(unsafe) items are moved in and out of the queue by value, and each slot is only accessed
+// by the producer that reserved it, then by the consumer once it has been published
+unsafe impl<T: Send, const N: usize> Sync for Queue<T, N> {}
+
+impl<T, const N: usize> Queue<T, N> {
+    /// Creates an empty queue.
+    #[inline]
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N.is_power_of_two(),
+                "the queue length must be a power of two"
+            )
+        };
+
+        Queue {
+            enqueue: AtomicUsize::new(0),
+            dequeue: AtomicUsize::new(0),
+            slots: [const { Slot::new() }; N],
+        }
+    }
+
+    /// Adds `item` at the back of the queue, or gives it back if the queue is full.
+    #[inline]
+    pub fn enqueue(&self, item: T) -> Result<(), T> {
+        let Some(pos) = self.reserve() else {
+            return Err(item);
+        };
+        let slot = self.slot(pos);
+
+        // NOTE(unsafe) the slot was reserved for `pos` by this producer only
+        unsafe { (*slot.item.get()).write(item) };
+        slot.sequence
+            .store(Self::relative(pos.wrapping_add(1), pos), Ordering::Release);
+        Ok(())
+    }
+
+    /// Removes the item at the front of the queue, if any.
+    ///
+    /// Returns `None` if the queue is empty, or if the producer of the front item has not
+    /// finished writing it yet.
+    ///
+    /// # Safety
+    ///
+    /// There must be a single consumer: this must not be called concurrently, for example from
+    /// two interrupt handlers that can preempt each other.
+    #[inline]
+    pub unsafe fn dequeue(&self) -> Option<T> {
+        let pos = self.dequeue.load(Ordering::Relaxed);
+        let slot = self.slot(pos);
+
+        let sequence = slot.sequence.load(Ordering::Acquire);
+        if sequence != Self::relative(pos.wrapping_add(1), pos) {
+            return None;
+        }
+
+        let item = (*slot.item.get()).assume_init_read();
+        self.dequeue.store(pos.wrapping_add(1), Ordering::Relaxed);
+        slot.sequence
+            .store(Self::relative(pos.wrapping_add(N), pos), Ordering::Release);
+        Some(item)
+    }
+
+    /// Returns `true` if the queue holds no published item.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        let pos = self.dequeue.load(Ordering::Relaxed);
+        self.slot(pos).sequence.load(Ordering::Acquire) != Self::relative(pos.wrapping_add(1), pos)
+    }
+
+    /// Reserves the slot of the next position, returning `None` if the queue is full.
+    #[cfg(not(armv6m))]
+    #[inline]
+    fn reserve(&self) -> Option<usize> {
+        let mut pos = self.enqueue.load(Ordering::Relaxed);
+        loop {
+            let sequence = self.slot(pos).sequence.load(Ordering::Acquire);
+            let diff = sequence.wrapping_sub(Self::relative(pos, pos)) as isize;
+            if diff == 0 {
+                match self.enqueue.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return Some(pos),
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return None;
+            } else {
+                pos = self.enqueue.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Reserves the slot of the next position, returning `None` if the queue is full.
+    ///
+    /// ARMv6-M has no compare-and-swap, so the position is reserved in a critical section.
+    #[cfg(armv6m)]
+    #[inline]
+    fn reserve(&self) -> Option<usize> {
+        crate::interrupt::free(|_| {
+            let pos = self.enqueue.load(Ordering::Relaxed);
+            let sequence = self.slot(pos).sequence.load(Ordering::Acquire);
+            if sequence != Self::relative(pos, pos) {
+                return None;
+            }
+            self.enqueue.store(pos.wrapping_add(1), Ordering::Relaxed);
+            Some(pos)
+        })
+    }
+
+    #[inline(always)]
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        &self.slots[pos & (N - 1)]
+    }
+
+    /// Converts the sequence number `sequence` of the slot of `pos` to its stored form.
+    #[inline(always)]
+    fn relative(sequence: usize, pos: usize) -> usize {
+        sequence.wrapping_sub(pos & (N - 1))
+    }
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for Queue<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        // NOTE(unsafe) `&mut self` makes this the only consumer
+        while unsafe { self.dequeue() }.is_some() {}
+    }
+}
+
+/// A software task lane: a queue of work items drained by the handler of a device interrupt
+pub struct Lane<I, T, const N: usize> {
+    interrupt: I,
+    queue: Queue<T, N>,
+}
+
+impl<I, T, const N: usize> Lane<I, T, N>
+where
+    I: InterruptNumber,
+{
+    /// Creates a lane whose work items are drained by the handler of `interrupt`.
+    #[inline]
+    pub const fn new(interrupt: I) -> Self {
+        Lane {
+            interrupt,
+            queue: Queue::new(),
+        }
+    }
+
+    /// Returns the interrupt of the lane.
+    #[inline]
+    pub fn interrupt(&self) -> I {
+        self.interrupt
+    }
+
+    /// Sets the priority of the lane interrupt and enables it.
+    ///
+    /// The handle of the interrupt is consumed: the interrupt is dedicated to the lane from now
+    /// on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` is not the handle of the lane interrupt.
+    ///
+    /// # Safety
+    ///
+    /// See [`InterruptHandle::set_priority`].
+    #[inline]
+    pub unsafe fn start<P>(&self, mut handle: InterruptHandle<I>, prio: P)
+    where
+        P: HwPriority,
+    {
+        assert_eq!(
+            handle.interrupt().number(),
+            self.interrupt.number(),
+            "the handle is not the one of the lane interrupt"
+        );
+        handle.unpend();
+        handle.set_priority(prio);
+        handle.enable();
+    }
+
+    /// Posts `item` to the lane and pends the lane interrupt, or gives `item` back if the queue
+    /// is full.
+    #[inline]
+    pub fn post(&self, item: T) -> Result<(), T> {
+        self.queue.enqueue(item)?;
+        self.pend();
+        Ok(())
+    }
+
+    /// Calls `f` on each work item of the lane, until the queue is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called from the handler of the lane interrupt, the only consumer of the
+    /// queue.
+    #[inline]
+    pub fn drain<F>(&self, mut f: F)
+    where
+        F: FnMut(T),
+    {
+        // NOTE(unsafe) atomic read with no side effects
+        let vectactive = unsafe { (*SCB::PTR).icsr.read() } & 0x1FF;
+        assert_eq!(
+            vectactive as usize,
+            self.interrupt.number() + 16,
+            "`Lane::drain` must be called from the handler of the lane interrupt"
+        );
+
+        // NOTE(unsafe) an interrupt handler does not preempt itself, so it is the only consumer
+        while let Some(item) = unsafe { self.queue.dequeue() } {
+            f(item);
+        }
+    }
+
+    /// Returns the queue of the lane.
+    #[inline]
+    pub fn queue(&self) -> &Queue<T, N> {
+        &self.queue
+    }
+
+    #[inline(always)]
+    fn pend(&self) {
+        #[cfg(not(armv6m))]
+        {
+            // NOTE(unsafe) atomic stateless write; STIR doesn't store any state
+            unsafe {
+                (*crate::peripheral::NVIC::PTR)
+                    .stir
+                    .write(self.interrupt.number() as u32)
+            }
+        }
+
+        #[cfg(armv6m)]
+        NVIC::pend(self.interrupt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue() {
+        let queue: Queue<u32, 4> = Queue::new();
+        assert!(queue.is_empty());
+        assert_eq!(unsafe { queue.dequeue() }, None);
+
+        // wrap around the slots several times
+        for round in 0..3 {
+            for i in 0..4 {
+                assert_eq!(queue.enqueue(round * 10 + i), Ok(()));
+            }
+            assert_eq!(queue.enqueue(99), Err(99));
+            assert!(!queue.is_empty());
+
+            for i in 0..4 {
+                assert_eq!(unsafe { queue.dequeue() }, Some(round * 10 + i));
+            }
+            assert_eq!(unsafe { queue.dequeue() }, None);
+        }
+
+        // interleaved
+        queue.enqueue(1).unwrap();
+        queue.enqueue(2).unwrap();
+        assert_eq!(unsafe { queue.dequeue() }, Some(1));
+        queue.enqueue(3).unwrap();
+        assert_eq!(unsafe { queue.dequeue() }, Some(2));
+        assert_eq!(unsafe { queue.dequeue() }, Some(3));
+        assert!(queue.is_empty());
+    }
+}
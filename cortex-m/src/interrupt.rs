@@ -1,36 +1,41 @@
 //! Interrupts
+//!
+//! Interrupt enums of PACs generated for older versions of this crate, which only implement
+//! `bare_metal::Nr`, implement [`InterruptNumber`] through a compatibility implementation and can
+//! be used with the [`NVIC`](crate::peripheral::NVIC) API unchanged:
+//!
+//! ``` no_run
+//! # use cortex_m::interrupt::Nr;
+//! # use cortex_m::peripheral::NVIC;
+//! #[derive(Clone, Copy)]
+//! enum Interrupt {
+//!     Usart1 = 37,
+//! }
+//!
+//! unsafe impl Nr for Interrupt {
+//!     fn nr(&self) -> u8 {
+//!         *self as u8
+//!     }
+//! }
+//!
+//! NVIC::mask(Interrupt::Usart1);
+//! ```
+//!
+//! As `Nr` provides no way back from a number to a variant, `InterruptNumber::from_number` always
+//! fails for them. This compatibility implementation will be removed in cortex-m 0.8.
 
 pub use bare_metal::{CriticalSection, Mutex, Nr};
+pub use cortex_m_types::{InterruptNumber, PriorityNumber};
 
-/// Trait for enums of external interrupt numbers.
-///
-/// This trait should be implemented by a peripheral access crate (PAC)
-/// on its enum of available external interrupts for a specific device.
-/// Each variant must convert to a u16 of its interrupt number,
-/// which is its exception number - 16.
-///
-/// # Safety
-///
-/// This trait must only be implemented on enums of device interrupts. Each
-/// enum variant must represent a distinct value (no duplicates are permitted),
-/// and must always return the same value (do not change at runtime).
-///
-/// These requirements ensure safe nesting of critical sections.
-pub unsafe trait InterruptNumber: Copy {
-    /// Return the interrupt number associated with this variant.
-    ///
-    /// See trait documentation for safety requirements.
-    fn number(self) -> u16;
-}
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+use core::cell::UnsafeCell;
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+use core::marker::PhantomData;
+#[cfg(all(cortex_m, not(armv6m), not(armv8m_base)))]
+use core::sync::atomic::{compiler_fence, Ordering};
 
-/// Implement InterruptNumber for the old bare_metal::Nr trait.
-/// This implementation is for backwards compatibility only and will be removed in cortex-m 0.8.
-unsafe impl<T: Nr + Copy> InterruptNumber for T {
-    #[inline]
-    fn number(self) -> u16 {
-        self.nr() as u16
-    }
-}
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+use crate::priority::HwPriority;
 
 /// Disables all interrupts
 #[inline]
@@ -87,3 +92,344 @@ where
 {
     panic!("cortex_m::interrupt::free() is only functional on cortex-m platforms");
 }
+
+/// Proof that all the interrupts with a priority at or below a ceiling are masked
+///
+/// This token is handed out by [`free_below`], and is used to access a [`CeilingMutex`] whose
+/// ceiling is not above the token's ceiling. `CEILING` is the hardware encoding of the ceiling;
+/// `0` means that all the interrupts are masked.
+///
+/// This type is not available on ARMv6-M and ARMv8-M Baseline, which do not implement BASEPRI.
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+#[derive(Debug)]
+pub struct PriorityCeiling<const CEILING: u8> {
+    _not_send: PhantomData<*const ()>,
+}
+
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+impl<const CEILING: u8> PriorityCeiling<CEILING> {
+    /// Returns the hardware encoding of the ceiling.
+    ///
+    /// `0` means that all the interrupts are masked.
+    #[inline]
+    pub fn hw(&self) -> u8 {
+        CEILING
+    }
+
+    /// Returns `true` if the interrupts at priority `prio` are masked.
+    #[inline]
+    pub fn masks<P: HwPriority>(&self, prio: P) -> bool {
+        masks_hw(CEILING, prio.to_hw())
+    }
+}
+
+/// Returns `true` if a ceiling whose hardware encoding is `ceiling` masks the interrupts whose
+/// hardware priority is `hw`.
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+#[inline(always)]
+const fn masks_hw(ceiling: u8, hw: u8) -> bool {
+    ceiling == 0 || hw >= ceiling
+}
+
+/// Execute closure `f` with all the interrupts at or below the priority whose hardware encoding
+/// is `CEILING` masked.
+///
+/// BASEPRI is raised to `CEILING` with `basepri_max`, so it is never lowered if an enclosing
+/// section already masks more interrupts, and restored on exit. Interrupts with a higher priority
+/// than `CEILING` keep running. If `CEILING` is `0`, the most urgent priority, which cannot be
+/// used with BASEPRI, all the interrupts are masked with PRIMASK instead.
+///
+/// This function is not available on ARMv6-M and ARMv8-M Baseline, which do not implement
+/// BASEPRI.
+///
+/// # Example
+///
+/// ```no_run
+/// use core::cell::Cell;
+/// use cortex_m::interrupt::{free_below, CeilingMutex};
+/// use cortex_m::priority::LogicalPriority;
+///
+/// // 3 priority bits, shared with interrupts up to level 4
+/// const CEILING: u8 = match LogicalPriority::new(4, 3) {
+///     Ok(prio) => prio.to_hw(),
+///     Err(_) => panic!(),
+/// };
+///
+/// // Only accessed from contexts at or below `CEILING`
+/// static COUNTER: CeilingMutex<Cell<u32>, CEILING> = unsafe { CeilingMutex::new(Cell::new(0)) };
+///
+/// free_below::<CEILING, _, _>(|ceiling| {
+///     let counter = COUNTER.borrow(ceiling);
+///     counter.set(counter.get() + 1);
+/// });
+/// ```
+#[cfg(all(cortex_m, not(armv6m), not(armv8m_base)))]
+#[inline]
+pub fn free_below<const CEILING: u8, F, R>(f: F) -> R
+where
+    F: FnOnce(&PriorityCeiling<CEILING>) -> R,
+{
+    let token = PriorityCeiling {
+        _not_send: PhantomData,
+    };
+
+    if CEILING == 0 {
+        return free(|_| f(&token));
+    }
+
+    let basepri = crate::register::basepri::read();
+    crate::register::basepri_max::write(CEILING);
+    compiler_fence(Ordering::SeqCst);
+
+    let r = f(&token);
+
+    compiler_fence(Ordering::SeqCst);
+    unsafe { crate::register::basepri::write(basepri) };
+
+    r
+}
+
+// Make a `free_below()` function available on hosted platforms to allow checking dependencies
+// without specifying a target, but that will panic at runtime if executed.
+/// Execute closure `f` with all the interrupts at or below the priority whose hardware encoding
+/// is `CEILING` masked.
+#[cfg(all(not(cortex_m), not(armv6m), not(armv8m_base)))]
+#[inline]
+pub fn free_below<const CEILING: u8, F, R>(_: F) -> R
+where
+    F: FnOnce(&PriorityCeiling<CEILING>) -> R,
+{
+    panic!("cortex_m::interrupt::free_below() is only functional on cortex-m platforms");
+}
+
+/// A mutex for data shared with interrupts at or below a priority ceiling
+///
+/// `CEILING` is the hardware encoding of the most urgent priority the data is accessed from. Like
+/// [`Mutex`], it only gives shared access to the data, so it is usually combined with a `Cell` or
+/// a `RefCell`. Access requires a [`PriorityCeiling`] token from [`free_below`] that masks
+/// `CEILING`, which is checked at compile time.
+///
+/// This type is not available on ARMv6-M and ARMv8-M Baseline, which do not implement BASEPRI.
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+pub struct CeilingMutex<T, const CEILING: u8> {
+    inner: UnsafeCell<T>,
+}
+
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+impl<T, const CEILING: u8> CeilingMutex<T, CEILING> {
+    /// Creates a new mutex, for data shared at or below the priority whose hardware encoding is
+    /// `CEILING`.
+    ///
+    /// # Safety
+    ///
+    /// The mutex must only be accessed from code running at a priority at or below `CEILING`.
+    /// Otherwise a more urgent interrupt could access the data while it is borrowed.
+    #[inline]
+    pub const unsafe fn new(value: T) -> Self {
+        CeilingMutex {
+            inner: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns the hardware encoding of the ceiling of the mutex.
+    #[inline]
+    pub fn ceiling(&self) -> u8 {
+        CEILING
+    }
+
+    /// Borrows the data for the duration of the masked section.
+    ///
+    /// The ceiling of `token` must mask the ceiling of the mutex, otherwise this does not compile:
+    ///
+    /// ```compile_fail
+    /// use core::cell::Cell;
+    /// use cortex_m::interrupt::{CeilingMutex, PriorityCeiling};
+    ///
+    /// static COUNTER: CeilingMutex<Cell<u32>, 0x40> = unsafe { CeilingMutex::new(Cell::new(0)) };
+    ///
+    /// fn bump(ceiling: &PriorityCeiling<0x80>) {
+    ///     COUNTER.borrow(ceiling).set(1);
+    /// }
+    /// # let _: fn(&PriorityCeiling<0x80>) = bump;
+    /// ```
+    #[inline]
+    pub fn borrow<'cs, const TOKEN: u8>(&'cs self, _token: &'cs PriorityCeiling<TOKEN>) -> &'cs T {
+        const {
+            assert!(
+                masks_hw(TOKEN, CEILING),
+                "priority ceiling of the token is below the ceiling of the mutex"
+            )
+        };
+        unsafe { &*self.inner.get() }
+    }
+
+    /// Borrows the data for the duration of an [`interrupt::free`](free) critical section.
+    #[inline]
+    pub fn borrow_cs<'cs>(&'cs self, _cs: &'cs CriticalSection) -> &'cs T {
+        unsafe { &*self.inner.get() }
+    }
+
+    /// Returns a mutable reference to the data.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+}
+
+// NOTE A `CeilingMutex` can be used as a channel so the protected data must be `Send` to prevent
+// sending non-Sendable stuff (e.g. access tokens) across different execution contexts.
+#[cfg(all(not(armv6m), not(armv8m_base)))]
+unsafe impl<T, const CEILING: u8> Sync for CeilingMutex<T, CEILING> where T: Send {}
+
+/// Enters a BASEPRI critical section. Used by `set_basepri_critical_section!`.
+///
+/// The returned state holds the previous BASEPRI value, or the previous PRIMASK value if
+/// `ceiling` is `0`, see [`BasepriState`].
+#[cfg(all(
+    cortex_m,
+    not(armv6m),
+    not(armv8m_base),
+    feature = "critical-section-basepri"
+))]
+#[doc(hidden)]
+#[inline]
+pub unsafe fn basepri_acquire(ceiling: u8) -> u32 {
+    if ceiling == 0 {
+        let primask = crate::register::primask::read_raw() & 1;
+        // NOTE: Fence guarantees are provided by disable(), which performs a `compiler_fence(SeqCst)`.
+        disable();
+        return BasepriState::Primask(primask).encode();
+    }
+
+    let basepri = crate::register::basepri::read();
+    crate::register::basepri_max::write(ceiling);
+    compiler_fence(Ordering::SeqCst);
+    BasepriState::Basepri(basepri).encode()
+}
+
+/// Leaves a BASEPRI critical section. Used by `set_basepri_critical_section!`.
+#[cfg(all(
+    cortex_m,
+    not(armv6m),
+    not(armv8m_base),
+    feature = "critical-section-basepri"
+))]
+#[doc(hidden)]
+#[inline]
+pub unsafe fn basepri_release(restore_state: u32) {
+    match BasepriState::decode(restore_state) {
+        BasepriState::Primask(primask) => {
+            // NOTE: Fence guarantees are provided by primask::write_raw(), which performs a `compiler_fence(SeqCst)`.
+            crate::register::primask::write_raw(primask);
+        }
+        BasepriState::Basepri(basepri) => {
+            compiler_fence(Ordering::SeqCst);
+            crate::register::basepri::write(basepri);
+        }
+    }
+}
+
+/// State restored when leaving a BASEPRI critical section
+#[cfg(all(
+    not(armv6m),
+    not(armv8m_base),
+    any(test, all(cortex_m, feature = "critical-section-basepri"))
+))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BasepriState {
+    /// BASEPRI value before the critical section
+    Basepri(u8),
+    /// PRIMASK value before the critical section, when the ceiling was `0`
+    Primask(u32),
+}
+
+#[cfg(all(
+    not(armv6m),
+    not(armv8m_base),
+    any(test, all(cortex_m, feature = "critical-section-basepri"))
+))]
+impl BasepriState {
+    /// Restore state bit set when a BASEPRI critical section fell back to PRIMASK.
+    const PRIMASK_STATE: u32 = 1 << 31;
+
+    #[inline(always)]
+    fn encode(self) -> u32 {
+        match self {
+            BasepriState::Basepri(basepri) => u32::from(basepri),
+            BasepriState::Primask(primask) => primask | Self::PRIMASK_STATE,
+        }
+    }
+
+    #[inline(always)]
+    fn decode(restore_state: u32) -> Self {
+        if restore_state & Self::PRIMASK_STATE != 0 {
+            BasepriState::Primask(restore_state & !Self::PRIMASK_STATE)
+        } else {
+            BasepriState::Basepri(restore_state as u8)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::priority::LogicalPriority;
+
+    #[test]
+    fn priority_ceiling_masks() {
+        assert!(masks_hw(0, 0));
+        assert!(masks_hw(0, 0xE0));
+        assert!(!masks_hw(0x40, 0x20));
+        assert!(masks_hw(0x40, 0x40));
+        assert!(masks_hw(0x40, 0xE0));
+
+        // 3 priority bits: level 4 is encoded as 0x60, level 5 as 0x40
+        let token = PriorityCeiling::<0x60> {
+            _not_send: PhantomData,
+        };
+        assert_eq!(token.hw(), 0x60);
+        assert!(token.masks(LogicalPriority::new(4, 3).unwrap()));
+        assert!(token.masks(LogicalPriority::new(1, 3).unwrap()));
+        assert!(!token.masks(LogicalPriority::new(5, 3).unwrap()));
+    }
+
+    #[test]
+    fn ceiling_mutex_borrow() {
+        let mutex: CeilingMutex<Cell<u32>, 0x60> = unsafe { CeilingMutex::new(Cell::new(1)) };
+        assert_eq!(mutex.ceiling(), 0x60);
+
+        let same = PriorityCeiling::<0x60> {
+            _not_send: PhantomData,
+        };
+        mutex.borrow(&same).set(2);
+
+        let above = PriorityCeiling::<0x20> {
+            _not_send: PhantomData,
+        };
+        assert_eq!(mutex.borrow(&above).get(), 2);
+
+        let all = PriorityCeiling::<0> {
+            _not_send: PhantomData,
+        };
+        assert_eq!(mutex.borrow(&all).get(), 2);
+    }
+
+    #[test]
+    fn basepri_state() {
+        assert_eq!(BasepriState::Basepri(0).encode(), 0);
+        assert_eq!(BasepriState::Basepri(0xE0).encode(), 0xE0);
+        assert_eq!(BasepriState::Primask(0).encode(), 1 << 31);
+        assert_eq!(BasepriState::Primask(1).encode(), (1 << 31) | 1);
+
+        for state in [
+            BasepriState::Basepri(0),
+            BasepriState::Basepri(0x40),
+            BasepriState::Primask(0),
+            BasepriState::Primask(1),
+        ] {
+            assert_eq!(BasepriState::decode(state.encode()), state);
+        }
+    }
+}
@@ -40,6 +40,41 @@
 //! configuration. For minimal interrupt latency, you can achieve inlining by enabling
 //! [linker-plugin-based LTO](https://doc.rust-lang.org/rustc/linker-plugin-lto.html).
 //!
+//! ## `critical-section-multi-core`
+//!
+//! This feature makes the `set_multi_core_critical_section!` macro available. It sets a
+//! [`critical-section`](https://github.com/rust-embedded/critical-section) implementation for
+//! multi-core targets, based on disabling interrupts on the running core and taking an inter-core
+//! lock provided by the device. See the [`multicore`] module for details.
+//!
+//! It cannot be enabled together with `critical-section-single-core`.
+//!
+//! ## `critical-section-basepri`
+//!
+//! This feature makes the `set_basepri_critical_section!` macro available. It sets a
+//! [`critical-section`](https://github.com/rust-embedded/critical-section) implementation for
+//! single-core targets that masks interrupts up to a priority ceiling with BASEPRI, leaving the
+//! more urgent interrupts running. The macro is invoked as
+//! `set_basepri_critical_section!(unsafe CEILING)`, as the handlers above the ceiling must not use
+//! `critical-section`. See also [`interrupt::free_below`].
+//!
+//! It is not available on ARMv6-M and ARMv8-M Baseline, and cannot be enabled together with the
+//! other `critical-section-*` features.
+//!
+//! ## `serde` and `defmt`
+//!
+//! These features implement [`serde`](https://serde.rs)'s `Serialize` and `Deserialize`, and
+//! [`defmt`](https://defmt.ferrous-systems.com)'s `Format`, on the register value types of this
+//! crate, such as `register::control::Control`, `peripheral::syst::SystClkSource` or
+//! `peripheral::scb::VectActive`. Both work in `no_std` builds, so devices can stream register
+//! snapshots to host tooling.
+//!
+//! ## `snapshot`
+//!
+//! This feature enables the [`peripheral::snapshot`] module, to capture, restore, encode and
+//! compare snapshots of the core peripheral registers. With the `serde_json` feature, snapshots
+//! can also be converted to and from JSON on the host.
+//!
 //! ## `cm7-r0p1`
 //!
 //! This feature enables workarounds for errata found on Cortex-M7 chips with revision r0p1. Some
@@ -68,7 +103,7 @@
 //!
 //! # Minimum Supported Rust Version (MSRV)
 //!
-//! This crate is guaranteed to compile on stable Rust 1.61 and up. It *might*
+//! This crate is guaranteed to compile on stable Rust 1.81 and up. It *might*
 //! compile with older versions but that may change in any new patch release.
 
 #![deny(missing_docs)]
@@ -94,6 +129,8 @@
 // Don't warn about feature(asm) being stable on Rust >= 1.59.0
 #![allow(stable_features)]
 
+#[cfg(feature = "serde_json")]
+extern crate alloc;
 extern crate bare_metal;
 extern crate volatile_register;
 
@@ -103,22 +140,51 @@ mod call_asm;
 mod macros;
 
 pub mod asm;
+pub mod boot;
 #[cfg(armv8m)]
 pub mod cmse;
 pub mod delay;
+pub mod dispatcher;
 pub mod interrupt;
 #[cfg(all(not(armv6m), not(armv8m_base)))]
 pub mod itm;
+pub mod multicore;
 pub mod peripheral;
+pub mod power;
 pub mod prelude;
+pub mod priority;
 pub mod psp;
 pub mod register;
+pub mod reset;
+#[cfg(not(any(armv6m, armv8m_base)))]
+pub mod task;
+pub mod vector_table;
 
 pub use crate::peripheral::Peripherals;
+pub use cortex_m_types::{result, CoreIdNumber, ExceptionNumber, InterruptNumber, PriorityNumber};
 
 #[cfg(all(cortex_m, feature = "critical-section-single-core"))]
 mod critical_section;
 
+#[cfg(all(
+    feature = "critical-section-single-core",
+    feature = "critical-section-multi-core"
+))]
+compile_error!(
+    "the `critical-section-single-core` and `critical-section-multi-core` features are mutually exclusive"
+);
+
+#[cfg(all(
+    feature = "critical-section-basepri",
+    any(
+        feature = "critical-section-single-core",
+        feature = "critical-section-multi-core"
+    )
+))]
+compile_error!(
+    "the `critical-section-basepri` feature cannot be combined with the other `critical-section-*` features"
+);
+
 /// Used to reexport items for use in macros. Do not use directly.
 /// Not covered by semver guarantees.
 #[doc(hidden)]
@@ -127,3 +127,134 @@ const CPASS: () = ();
 /// ```
 #[allow(dead_code)]
 const CPASS_ATTR: () = ();
+
+/// Sets a `critical-section` implementation for multi-core devices
+///
+/// The critical section disables interrupts on the running core and takes an inter-core lock
+/// shared by all the cores, so that a critical section entered on one core excludes every other
+/// core. Nested critical sections on the same core do not take the lock again.
+///
+/// The first argument is the PAC's core identifier enum, which must implement
+/// [`multicore::CurrentCore`](crate::multicore::CurrentCore). The second argument is a type
+/// implementing [`multicore::InterCoreLock`](crate::multicore::InterCoreLock), usually wrapping a
+/// hardware semaphore of the device.
+///
+/// This macro is only available with the `critical-section-multi-core` feature, and must be
+/// invoked once in the final application (or in the HAL it depends on).
+///
+/// # Example
+///
+/// ``` ignore
+/// use cortex_m::multicore::InterCoreLock;
+///
+/// struct HardwareSpinlock;
+///
+/// unsafe impl InterCoreLock for HardwareSpinlock {
+///     unsafe fn lock() {
+///         while !pac::SIO::steal().spinlock(31).try_claim() {}
+///     }
+///
+///     unsafe fn unlock() {
+///         pac::SIO::steal().spinlock(31).release();
+///     }
+/// }
+///
+/// cortex_m::set_multi_core_critical_section!(pac::CoreId, HardwareSpinlock);
+/// ```
+#[cfg(all(cortex_m, feature = "critical-section-multi-core"))]
+#[macro_export]
+macro_rules! set_multi_core_critical_section {
+    ($core:ty, $lock:ty) => {
+        const _: () = {
+            struct MultiCoreCriticalSection;
+            $crate::_export::critical_section::set_impl!(MultiCoreCriticalSection);
+
+            unsafe impl $crate::_export::critical_section::Impl for MultiCoreCriticalSection {
+                unsafe fn acquire() -> $crate::_export::critical_section::RawRestoreState {
+                    $crate::multicore::acquire::<$core, $lock>()
+                }
+
+                unsafe fn release(
+                    restore_state: $crate::_export::critical_section::RawRestoreState,
+                ) {
+                    $crate::multicore::release::<$core, $lock>(restore_state)
+                }
+            }
+        };
+    };
+}
+
+/// Sets a [`critical-section`](https://github.com/rust-embedded/critical-section) implementation
+/// based on BASEPRI
+///
+/// The critical section raises BASEPRI to the given ceiling, the hardware encoding of a priority
+/// (for example [`LogicalPriority::to_hw`](crate::priority::LogicalPriority::to_hw)), so
+/// interrupts above the ceiling keep running with the lowest latency. A ceiling of `0` masks all
+/// the interrupts with PRIMASK, like `critical-section-single-core`.
+///
+/// This macro is only available with the `critical-section-basepri` feature, on architectures
+/// implementing BASEPRI, and must be invoked once in the final application. The ceiling is
+/// prefixed with `unsafe`, see below.
+///
+/// # Safety
+///
+/// The critical section does not mask the exceptions and interrupts whose priority is above the
+/// ceiling, so:
+///
+/// - their handlers must not use `critical-section`, nor access data protected by it;
+/// - every other handler that uses `critical-section` must run at a priority at or below the
+///   ceiling. This includes SysTick and PendSV, which reset to the most urgent priority, `0`, and
+///   can be enabled with safe APIs such as `SYST::enable_interrupt` and `SCB::set_pendsv`, as
+///   well as the interrupts enabled with `InterruptHandle::enable` or `NVIC::unmask`.
+///
+/// It is also unsound on multi-core targets and for code running in unprivileged mode.
+///
+/// # Example
+///
+/// ``` ignore
+/// use cortex_m::priority::LogicalPriority;
+///
+/// // 4 priority bits; levels 13 to 15 are reserved for latency-critical interrupts
+/// const CEILING: u8 = match LogicalPriority::new(12, 4) {
+///     Ok(prio) => prio.to_hw(),
+///     Err(_) => panic!(),
+/// };
+///
+/// // SAFETY: the handlers of levels 13 to 15 do not use `critical-section`, and all the other
+/// // exceptions and interrupts are given a level of 12 or less before being enabled
+/// cortex_m::set_basepri_critical_section!(unsafe CEILING);
+/// ```
+#[cfg(all(
+    cortex_m,
+    not(armv6m),
+    not(armv8m_base),
+    feature = "critical-section-basepri"
+))]
+#[macro_export]
+macro_rules! set_basepri_critical_section {
+    (unsafe $ceiling:expr) => {
+        const _: () = {
+            const BASEPRI_CEILING: u8 = $ceiling;
+
+            struct BasepriCriticalSection;
+            $crate::_export::critical_section::set_impl!(BasepriCriticalSection);
+
+            unsafe impl $crate::_export::critical_section::Impl for BasepriCriticalSection {
+                unsafe fn acquire() -> $crate::_export::critical_section::RawRestoreState {
+                    $crate::interrupt::basepri_acquire(BASEPRI_CEILING)
+                }
+
+                unsafe fn release(
+                    restore_state: $crate::_export::critical_section::RawRestoreState,
+                ) {
+                    $crate::interrupt::basepri_release(restore_state)
+                }
+            }
+        };
+    };
+    ($ceiling:expr) => {
+        ::core::compile_error!(
+            "the ceiling must be prefixed with `unsafe`: `set_basepri_critical_section!(unsafe CEILING)`, see the `# Safety` section of the macro"
+        );
+    };
+}
@@ -0,0 +1,157 @@
+//! Multi-core support
+//!
+//! The core peripherals live in the Private Peripheral Bus, which is banked per core: every core
+//! of a multi-core device accesses its *own* NVIC, SCB, SysTick, etc. at the same addresses. This
+//! module lets a peripheral access crate (PAC) describe the cores of a device so that ownership of
+//! core-local resources can be tracked per core.
+//!
+//! The PAC implements [`CurrentCore`] on its [`CoreIdNumber`] enum, using whatever mechanism the
+//! device provides to identify the running core (a vendor CPUID register, the `CPUID.PARTNO`
+//! field on heterogeneous devices, ...). With it:
+//!
+//! - [`current_core`] returns the core executing the caller.
+//! - [`Peripherals::take_for_core`](crate::Peripherals::take_for_core) hands out the core
+//!   peripherals once *per core*.
+//! - `set_multi_core_critical_section!`, with the `critical-section-multi-core` feature, provides a
+//!   `critical-section` implementation that is sound on multi-core devices, given an
+//!   [`InterCoreLock`] (typically a hardware semaphore or spinlock of the device).
+
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+use core::sync::atomic::AtomicBool;
+
+use crate::CoreIdNumber;
+use cortex_m_types::result::{Error, Result};
+
+/// Highest number of cores supported by the per-core bookkeeping of this crate.
+pub const MAX_CORES: usize = 32;
+
+/// Per-core flags used by [`Peripherals::take_for_core`](crate::Peripherals::take_for_core).
+///
+/// Each flag is only written by its own core, with interrupts disabled.
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+pub(crate) static TAKEN_PER_CORE: [AtomicBool; MAX_CORES] =
+    [const { AtomicBool::new(false) }; MAX_CORES];
+
+/// Trait for enums of core identifiers that can identify the running core.
+///
+/// # Safety
+///
+/// `current` must return the identifier of the core executing the call. Returning the identifier
+/// of another core makes per-core singletons alias.
+pub unsafe trait CurrentCore: CoreIdNumber {
+    /// Returns the identifier of the core executing this call.
+    fn current() -> Self;
+}
+
+/// Trait for locks shared by all the cores of a device.
+///
+/// # Safety
+///
+/// - `lock` must not return while another core holds the lock.
+/// - The lock must provide acquire (`lock`) and release (`unlock`) semantics for memory accesses
+///   of all the cores, including when data caches are enabled.
+pub unsafe trait InterCoreLock {
+    /// Acquires the lock, spinning until it is available.
+    ///
+    /// # Safety
+    ///
+    /// Must not be called by a core that already holds the lock.
+    unsafe fn lock();
+
+    /// Releases the lock.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the core that holds the lock.
+    unsafe fn unlock();
+}
+
+/// Returns the identifier of the core executing this call.
+#[inline]
+pub fn current_core<C: CurrentCore>() -> C {
+    C::current()
+}
+
+/// Returns `true` if `core` is the core executing this call.
+#[inline]
+pub fn is_current_core<C: CurrentCore>(core: C) -> bool {
+    core.number() == C::current().number()
+}
+
+/// Returns `Ok(())` if `core` is the core executing this call, or [`Error::WrongCore`] otherwise.
+#[inline]
+pub fn check_current_core<C: CurrentCore>(core: C) -> Result<()> {
+    let expected = core.number();
+    let current = C::current().number();
+
+    if expected == current {
+        Ok(())
+    } else {
+        Err(Error::WrongCore { expected, current })
+    }
+}
+
+/// Returns the index of `core` in the per-core bookkeeping tables.
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+#[inline(always)]
+pub(crate) fn core_index<C: CoreIdNumber>(core: C) -> usize {
+    const { assert!(C::MAX_CORE_ID_NUMBER < MAX_CORES) };
+
+    core.number()
+}
+
+#[cfg(all(cortex_m, feature = "critical-section-multi-core"))]
+#[doc(hidden)]
+pub use self::critical_section::{acquire, release};
+
+#[cfg(all(cortex_m, feature = "critical-section-multi-core"))]
+mod critical_section {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use super::{core_index, CurrentCore, InterCoreLock, MAX_CORES};
+    use crate::interrupt;
+    use crate::register::primask;
+
+    /// Restore state bit set when the matching `acquire` took the inter-core lock.
+    const LOCKED: u32 = 1 << 31;
+
+    /// Per-core flags set while a core holds the inter-core lock.
+    ///
+    /// Each flag is only accessed by its own core, with interrupts disabled, which is why no
+    /// coherency between the cores is needed.
+    static HOLDING: [AtomicBool; MAX_CORES] = [const { AtomicBool::new(false) }; MAX_CORES];
+
+    /// Enters a multi-core critical section. Used by `set_multi_core_critical_section!`.
+    ///
+    /// Interrupts are disabled on the running core, and the inter-core lock is taken unless the
+    /// running core already holds it (nested critical section).
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn acquire<C: CurrentCore, L: InterCoreLock>() -> u32 {
+        // See `SingleCoreCriticalSection` for why PRIMASK is read raw.
+        let primask = primask::read_raw() & 1;
+        interrupt::disable();
+
+        let holding = &HOLDING[core_index(C::current())];
+        if holding.load(Ordering::Relaxed) {
+            primask
+        } else {
+            L::lock();
+            holding.store(true, Ordering::Relaxed);
+            primask | LOCKED
+        }
+    }
+
+    /// Leaves a multi-core critical section. Used by `set_multi_core_critical_section!`.
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn release<C: CurrentCore, L: InterCoreLock>(restore_state: u32) {
+        if restore_state & LOCKED != 0 {
+            HOLDING[core_index(C::current())].store(false, Ordering::Relaxed);
+            L::unlock();
+        }
+
+        // NOTE: Fence guarantees are provided by primask::write_raw(), which performs a `compiler_fence(SeqCst)`.
+        primask::write_raw(restore_state & !LOCKED);
+    }
+}
@@ -1,11 +1,14 @@
 //! CPUID
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use volatile_register::RO;
 #[cfg(not(armv6m))]
 use volatile_register::RW;
 
-#[cfg(not(armv6m))]
 use crate::peripheral::CPUID;
+#[cfg(not(armv6m))]
+use cortex_m_types::result::{Error, Result};
 
 /// Register block
 #[repr(C)]
@@ -67,6 +70,8 @@ pub struct RegisterBlock {
 /// Type of cache to select on CSSELR writes.
 #[cfg(not(armv6m))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum CsselrCacheType {
     /// Select DCache or unified cache
     DataOrUnified = 0,
@@ -74,6 +79,217 @@ pub enum CsselrCacheType {
     Instruction = 1,
 }
 
+/// Processor identification and features, as returned by [`CPUID::info`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CpuInfo {
+    /// Implementer of the processor
+    pub implementer: Implementer,
+    /// Processor part
+    pub part: Part,
+    /// Major revision number (the `n` of `rnpm`)
+    pub variant: u8,
+    /// Minor revision number (the `m` of `rnpm`)
+    pub revision: u8,
+    /// Optional features implemented by the processor
+    pub features: Features,
+}
+
+/// Implementer code of the CPUID base register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Implementer {
+    /// Arm Limited
+    Arm,
+    /// Another implementer code
+    Other(u8),
+}
+
+/// Part number of the CPUID base register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Part {
+    /// Cortex-M0
+    CortexM0,
+    /// Cortex-M0+
+    CortexM0Plus,
+    /// Cortex-M1
+    CortexM1,
+    /// Cortex-M3
+    CortexM3,
+    /// Cortex-M4
+    CortexM4,
+    /// Cortex-M7
+    CortexM7,
+    /// Cortex-M23
+    CortexM23,
+    /// Cortex-M33
+    CortexM33,
+    /// Cortex-M35P
+    CortexM35P,
+    /// Cortex-M52
+    CortexM52,
+    /// Cortex-M55
+    CortexM55,
+    /// Cortex-M85
+    CortexM85,
+    /// Another part number
+    Other(u16),
+}
+
+/// Floating-point support
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FpuSupport {
+    /// No floating-point unit
+    None,
+    /// Single-precision floating-point unit
+    Single,
+    /// Single and double-precision floating-point unit
+    Double,
+}
+
+/// M-profile Vector Extension (Helium) support
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MveSupport {
+    /// No MVE
+    None,
+    /// Integer MVE
+    Integer,
+    /// Integer and floating-point MVE
+    IntegerAndFloat,
+}
+
+/// Optional features of the processor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Features {
+    /// DSP extension, from ID_ISAR3
+    pub dsp: bool,
+    /// Floating-point unit, from MVFR0
+    pub fpu: FpuSupport,
+    /// M-profile Vector Extension, from MVFR1
+    pub mve: MveSupport,
+    /// Security Extension (TrustZone), from ID_PFR1
+    pub trustzone: bool,
+}
+
+const CPUID_IMPLEMENTER_POS: u32 = 24;
+const CPUID_VARIANT_POS: u32 = 20;
+const CPUID_PARTNO_POS: u32 = 4;
+const CPUID_PARTNO_MASK: u32 = 0xFFF << CPUID_PARTNO_POS;
+const CPUID_REVISION_POS: u32 = 0;
+const ID_PFR1_SECURITY_POS: u32 = 4;
+const ID_ISAR3_SIMD_POS: u32 = 4;
+const MVFR0_SP_POS: u32 = 4;
+const MVFR0_DP_POS: u32 = 8;
+const MVFR1_MVE_POS: u32 = 8;
+
+/// Media and VFP Feature registers 0 and 1, in the Floating Point Unit block
+#[cfg(not(any(armv6m, armv8m_base)))]
+const MVFR: *const [u32; 2] = 0xE000_EF40 as *const _;
+
+impl CpuInfo {
+    /// Decodes the CPUID base, ID_PFR1, ID_ISAR3, MVFR0 and MVFR1 registers.
+    ///
+    /// Registers that are not implemented by the processor must be passed as zero.
+    #[inline]
+    pub fn from_registers(base: u32, id_pfr1: u32, id_isar3: u32, mvfr0: u32, mvfr1: u32) -> Self {
+        let field = |reg: u32, pos: u32| ((reg >> pos) & 0xF) as u8;
+
+        let implementer = match (base >> CPUID_IMPLEMENTER_POS) as u8 {
+            0x41 => Implementer::Arm,
+            code => Implementer::Other(code),
+        };
+
+        let part = match ((base & CPUID_PARTNO_MASK) >> CPUID_PARTNO_POS) as u16 {
+            0xC20 => Part::CortexM0,
+            0xC60 => Part::CortexM0Plus,
+            0xC21 => Part::CortexM1,
+            0xC23 => Part::CortexM3,
+            0xC24 => Part::CortexM4,
+            0xC27 => Part::CortexM7,
+            0xD20 => Part::CortexM23,
+            0xD21 => Part::CortexM33,
+            0xD31 => Part::CortexM35P,
+            0xD24 => Part::CortexM52,
+            0xD22 => Part::CortexM55,
+            0xD23 => Part::CortexM85,
+            partno => Part::Other(partno),
+        };
+
+        let fpu = if field(mvfr0, MVFR0_DP_POS) != 0 {
+            FpuSupport::Double
+        } else if field(mvfr0, MVFR0_SP_POS) != 0 {
+            FpuSupport::Single
+        } else {
+            FpuSupport::None
+        };
+
+        let mve = match field(mvfr1, MVFR1_MVE_POS) {
+            0 => MveSupport::None,
+            1 => MveSupport::Integer,
+            _ => MveSupport::IntegerAndFloat,
+        };
+
+        CpuInfo {
+            implementer,
+            part,
+            variant: field(base, CPUID_VARIANT_POS),
+            revision: field(base, CPUID_REVISION_POS),
+            features: Features {
+                // SIMD_instrs is 0b0001 for SSAT/USAT only, 0b0011 with the DSP extension
+                dsp: field(id_isar3, ID_ISAR3_SIMD_POS) >= 0b0011,
+                fpu,
+                mve,
+                trustzone: field(id_pfr1, ID_PFR1_SECURITY_POS) != 0,
+            },
+        }
+    }
+}
+
+impl CPUID {
+    /// Returns the identification and the optional features of the processor.
+    #[inline]
+    pub fn info() -> CpuInfo {
+        // NOTE(unsafe) atomic reads with no side effects
+        unsafe {
+            let cpuid = &*Self::PTR;
+
+            #[cfg(armv6m)]
+            let (id_pfr1, id_isar3) = (0, 0);
+            #[cfg(not(armv6m))]
+            let (id_pfr1, id_isar3) = (cpuid.pfr[1].read(), cpuid.isar[3].read());
+
+            #[cfg(any(armv6m, armv8m_base))]
+            let (mvfr0, mvfr1) = (0, 0);
+            #[cfg(not(any(armv6m, armv8m_base)))]
+            let (mvfr0, mvfr1) = (
+                core::ptr::addr_of!((*MVFR)[0]).read_volatile(),
+                core::ptr::addr_of!((*MVFR)[1]).read_volatile(),
+            );
+
+            CpuInfo::from_registers(cpuid.base.read(), id_pfr1, id_isar3, mvfr0, mvfr1)
+        }
+    }
+}
+
+#[cfg(not(armv6m))]
+const CSSELR_IND_POS: u32 = 0;
+#[cfg(not(armv6m))]
+const CSSELR_IND_MASK: u32 = 1 << CSSELR_IND_POS;
+#[cfg(not(armv6m))]
+const CSSELR_LEVEL_POS: u32 = 1;
+#[cfg(not(armv6m))]
+const CSSELR_LEVEL_MASK: u32 = 0x7 << CSSELR_LEVEL_POS;
+
 #[cfg(not(armv6m))]
 impl CPUID {
     /// Selects the current CCSIDR
@@ -84,11 +300,6 @@ impl CPUID {
     /// `level` is masked to be between 0 and 7.
     #[inline]
     pub fn select_cache(&mut self, level: u8, ind: CsselrCacheType) {
-        const CSSELR_IND_POS: u32 = 0;
-        const CSSELR_IND_MASK: u32 = 1 << CSSELR_IND_POS;
-        const CSSELR_LEVEL_POS: u32 = 1;
-        const CSSELR_LEVEL_MASK: u32 = 0x7 << CSSELR_LEVEL_POS;
-
         unsafe {
             self.csselr.write(
                 ((u32::from(level) << CSSELR_LEVEL_POS) & CSSELR_LEVEL_MASK)
@@ -97,6 +308,25 @@ impl CPUID {
         }
     }
 
+    /// Selects the current CCSIDR
+    ///
+    /// Same as [`CPUID::select_cache`], but returns [`Error::IndexOutOfBounds`] instead of masking
+    /// `level` if it is greater than 7.
+    #[inline]
+    pub fn try_select_cache(&mut self, level: u8, ind: CsselrCacheType) -> Result<()> {
+        const MAX_LEVEL: u32 = CSSELR_LEVEL_MASK >> CSSELR_LEVEL_POS;
+
+        if u32::from(level) > MAX_LEVEL {
+            return Err(Error::IndexOutOfBounds {
+                index: usize::from(level),
+                min: 0,
+                max: MAX_LEVEL as usize,
+            });
+        }
+        self.select_cache(level, ind);
+        Ok(())
+    }
+
     /// Returns the number of sets and ways in the selected cache
     #[inline]
     pub fn cache_num_sets_ways(&mut self, level: u8, ind: CsselrCacheType) -> (u16, u16) {
@@ -21,6 +21,30 @@
 //! let ok = Peripherals::take().unwrap();
 //! let panics = Peripherals::take().unwrap();
 //! ```
+//!
+//! On multi-core devices each core has its own core peripherals. They are taken once per core
+//! with [`Peripherals::take_for_core`], using the core identifiers of the PAC:
+//!
+//! ``` no_run
+//! # use cortex_m::multicore::CurrentCore;
+//! # use cortex_m::peripheral::Peripherals;
+//! # use cortex_m::result::{Error, Result};
+//! # use cortex_m::CoreIdNumber;
+//! # #[derive(Clone, Copy)]
+//! # enum CoreId { Cm7 = 0, Cm4 = 1 }
+//! # unsafe impl CoreIdNumber for CoreId {
+//! #     const MAX_CORE_ID_NUMBER: usize = 1;
+//! #     fn number(self) -> usize { self as usize }
+//! #     fn from_number(n: usize) -> Result<Self> { Err(Error::InvalidVariant(n)) }
+//! # }
+//! # unsafe impl CurrentCore for CoreId {
+//! #     fn current() -> Self { CoreId::Cm7 }
+//! # }
+//! // on the Cortex-M7 core
+//! let cm7 = Peripherals::take_for_core(CoreId::Cm7).unwrap();
+//! // fails: the Cortex-M4 peripherals must be taken from the Cortex-M4 core
+//! assert!(Peripherals::take_for_core(CoreId::Cm4).is_err());
+//! ```
 //! A part of the peripheral API doesn't require access to a peripheral instance. This part of the
 //! API is provided as static methods on the peripheral types. One example is the
 //! [`DWT::cycle_count`](struct.DWT.html#method.cycle_count) method.
@@ -59,6 +83,16 @@
 
 use core::marker::PhantomData;
 use core::ops;
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+use crate::multicore::{check_current_core, core_index, CurrentCore, TAKEN_PER_CORE};
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+use crate::CoreIdNumber;
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+use cortex_m_types::result::{Error, Result};
 
 #[cfg(feature = "cm7")]
 pub mod ac;
@@ -77,9 +111,11 @@ pub mod icb;
 pub mod itm;
 pub mod mpu;
 pub mod nvic;
-#[cfg(armv8m)]
+#[cfg(any(armv8m, native))]
 pub mod sau;
 pub mod scb;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
 pub mod syst;
 #[cfg(not(armv6m))]
 pub mod tpiu;
@@ -156,27 +192,153 @@ pub struct Peripherals {
 #[no_mangle]
 static CORE_PERIPHERALS: () = ();
 
-/// Set to `true` when `take` or `steal` was called to make `Peripherals` a singleton.
-static mut TAKEN: bool = false;
+/// Ownership of the core peripherals, one of `UNCLAIMED`, `ALL_CORES` or `PER_CORE`.
+///
+/// It is shared by all the cores, so it is only updated with atomic read-modify-write operations,
+/// or under the inter-core lock of the `critical-section-multi-core` feature on architectures
+/// without them.
+static OWNERSHIP: AtomicU8 = AtomicU8::new(UNCLAIMED);
+
+/// Neither `take` nor `take_for_core` have been used.
+const UNCLAIMED: u8 = 0;
+/// The core peripherals were taken with `take` or `steal`.
+const ALL_CORES: u8 = 1;
+/// The core peripherals are taken per core with `take_for_core` or `steal_for_core`.
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+const PER_CORE: u8 = 2;
+
+/// Sets `ownership` to `mode` if the core peripherals are unclaimed, and returns the previous
+/// ownership.
+#[cfg(target_has_atomic = "8")]
+#[inline]
+fn claim(ownership: &AtomicU8, mode: u8) -> u8 {
+    match ownership.compare_exchange(UNCLAIMED, mode, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(previous) | Err(previous) => previous,
+    }
+}
+
+/// Sets `ownership` to `mode` if the core peripherals are unclaimed, and returns the previous
+/// ownership.
+///
+/// Without atomic read-modify-write instructions (ARMv6-M), the update is made atomic with the
+/// inter-core lock of the `critical-section-multi-core` feature, or by masking interrupts on
+/// single-core devices.
+#[cfg(not(target_has_atomic = "8"))]
+#[inline]
+fn claim(ownership: &AtomicU8, mode: u8) -> u8 {
+    let claim = || {
+        let previous = ownership.load(Ordering::Acquire);
+        if previous == UNCLAIMED {
+            ownership.store(mode, Ordering::Release);
+        }
+        previous
+    };
+
+    #[cfg(feature = "critical-section-multi-core")]
+    {
+        critical_section::with(|_| claim())
+    }
+    #[cfg(not(feature = "critical-section-multi-core"))]
+    {
+        crate::interrupt::free(|_| claim())
+    }
+}
+
+/// Claims the core peripherals of a single core, whose flag is `taken`, and sets `ownership` to
+/// `PER_CORE` if they are unclaimed.
+///
+/// Returns [`Error::AlreadyTaken`] if the core peripherals of all the cores, or those of this
+/// core, have already been taken.
+#[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+#[inline]
+fn claim_core(ownership: &AtomicU8, taken: &AtomicBool) -> Result<()> {
+    if claim(ownership, PER_CORE) == ALL_CORES {
+        return Err(Error::AlreadyTaken);
+    }
+
+    #[cfg(target_has_atomic = "8")]
+    let was_taken = taken.swap(true, Ordering::Relaxed);
+    // The flag of a core is only accessed from the core itself, so disabling interrupts is
+    // enough to make the check and the update atomic.
+    #[cfg(not(target_has_atomic = "8"))]
+    let was_taken = crate::interrupt::free(|_| {
+        let was_taken = taken.load(Ordering::Relaxed);
+        taken.store(true, Ordering::Relaxed);
+        was_taken
+    });
+
+    if was_taken {
+        Err(Error::AlreadyTaken)
+    } else {
+        Ok(())
+    }
+}
 
 impl Peripherals {
     /// Returns all the core peripherals *once*
+    ///
+    /// On multi-core devices the core peripherals are banked per core, so `take` hands them out
+    /// to a single core of the whole device: it returns `None` once `take` or
+    /// [`Peripherals::take_for_core`] have been used on any core. Use `take_for_core` to get the
+    /// peripherals of each core.
+    ///
+    /// On ARMv6-M, which lacks atomic read-modify-write instructions, this is only synchronized
+    /// between cores with the `critical-section-multi-core` feature.
     #[inline]
     pub fn take() -> Option<Self> {
-        crate::interrupt::free(|_| {
-            if unsafe { TAKEN } {
-                None
-            } else {
-                Some(unsafe { Peripherals::steal() })
-            }
-        })
+        if claim(&OWNERSHIP, ALL_CORES) == UNCLAIMED {
+            Some(unsafe { Self::conjure() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the core peripherals of `core` *once*
+    ///
+    /// Each core of a multi-core device has its own set of core peripherals, so they can be taken
+    /// once per core, from the core itself. The running core is identified through the PAC's
+    /// [`CurrentCore`] implementation.
+    ///
+    /// Returns [`Error::WrongCore`] if `core` is not the running core, and [`Error::AlreadyTaken`]
+    /// if the peripherals of `core` have already been taken, or if [`Peripherals::take`] or
+    /// [`Peripherals::steal`] have been used.
+    ///
+    /// This is only available where the claim can be synchronized between cores: on
+    /// architectures with atomic read-modify-write instructions, or with the
+    /// `critical-section-multi-core` feature.
+    #[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+    #[inline]
+    pub fn take_for_core<C: CurrentCore>(core: C) -> Result<Self> {
+        check_current_core(core)?;
+        claim_core(&OWNERSHIP, &TAKEN_PER_CORE[core_index(core)])?;
+        Ok(unsafe { Self::conjure() })
     }
 
     /// Unchecked version of `Peripherals::take`
     #[inline]
     pub unsafe fn steal() -> Self {
-        TAKEN = true;
+        OWNERSHIP.store(ALL_CORES, Ordering::Release);
 
+        Self::conjure()
+    }
+
+    /// Unchecked version of `Peripherals::take_for_core`
+    ///
+    /// # Safety
+    ///
+    /// Must be called from `core`, and the returned peripherals alias any other instance owned by
+    /// the same core.
+    #[cfg(any(target_has_atomic = "8", feature = "critical-section-multi-core"))]
+    #[inline]
+    pub unsafe fn steal_for_core<C: CoreIdNumber>(core: C) -> Self {
+        claim(&OWNERSHIP, PER_CORE);
+        TAKEN_PER_CORE[core_index(core)].store(true, Ordering::Relaxed);
+
+        Self::conjure()
+    }
+
+    #[inline(always)]
+    unsafe fn conjure() -> Self {
         Peripherals {
             #[cfg(feature = "cm7")]
             AC: AC {
@@ -573,7 +735,7 @@ pub struct SAU {
 
 unsafe impl Send for SAU {}
 
-#[cfg(armv8m)]
+#[cfg(any(armv8m, native))]
 impl SAU {
     /// Pointer to the register block
     pub const PTR: *const sau::RegisterBlock = 0xE000_EDD0 as *const _;
@@ -586,7 +748,7 @@ impl SAU {
     }
 }
 
-#[cfg(armv8m)]
+#[cfg(any(armv8m, native))]
 impl ops::Deref for SAU {
     type Target = self::sau::RegisterBlock;
 
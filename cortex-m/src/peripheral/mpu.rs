@@ -1,7 +1,11 @@
 //! Memory Protection Unit
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use volatile_register::{RO, RW};
 
+use crate::peripheral::MPU;
+
 /// Register block for ARMv7-M
 #[cfg(not(armv8m))]
 #[repr(C)]
@@ -63,3 +67,1122 @@ pub struct RegisterBlock {
     /// Memory Attribute Indirection register 0 and 1
     pub mair: [RW<u32>; 2],
 }
+
+/// Control register bits
+const MPU_CTRL_ENABLE: u32 = 1 << 0;
+const MPU_CTRL_HFNMIENA: u32 = 1 << 1;
+const MPU_CTRL_PRIVDEFENA: u32 = 1 << 2;
+
+/// Options of [`MPU::enable`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MpuOptions {
+    /// Use the default memory map as a background region for privileged accesses
+    /// (`PRIVDEFENA`).
+    pub privileged_default_map: bool,
+    /// Keep the MPU enabled in the HardFault and NMI handlers, and when FAULTMASK is set
+    /// (`HFNMIENA`).
+    pub enabled_in_fault_handlers: bool,
+}
+
+impl MPU {
+    /// Returns the number of regions supported by the MPU, `0` if there is no MPU.
+    #[inline]
+    pub fn region_count(&self) -> u8 {
+        (self._type.read() >> 8) as u8
+    }
+
+    /// Enables the MPU.
+    ///
+    /// The barriers make sure that the following memory accesses and instruction fetches use the
+    /// new configuration.
+    #[inline]
+    pub fn enable(&mut self, options: MpuOptions) {
+        let mut ctrl = MPU_CTRL_ENABLE;
+        if options.privileged_default_map {
+            ctrl |= MPU_CTRL_PRIVDEFENA;
+        }
+        if options.enabled_in_fault_handlers {
+            ctrl |= MPU_CTRL_HFNMIENA;
+        }
+
+        crate::asm::dsb();
+        // NOTE(unsafe) we have exclusive access through `&mut self`
+        unsafe { self.ctrl.write(ctrl) };
+        crate::asm::dsb();
+        crate::asm::isb();
+    }
+
+    /// Disables the MPU.
+    #[inline]
+    pub fn disable(&mut self) {
+        crate::asm::dmb();
+        // NOTE(unsafe) we have exclusive access through `&mut self`
+        unsafe { self.ctrl.write(0) };
+        crate::asm::dsb();
+        crate::asm::isb();
+    }
+
+    /// Returns `true` if the MPU is enabled.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.ctrl.read() & MPU_CTRL_ENABLE != 0
+    }
+}
+
+#[cfg(all(not(armv6m), not(armv8m)))]
+pub use self::v7::*;
+
+#[cfg(all(not(armv6m), not(armv8m)))]
+mod v7 {
+    use cortex_m_types::result::{Error, Result};
+    #[cfg(feature = "serde")]
+    use serde::{Deserialize, Serialize};
+
+    use crate::peripheral::MPU;
+
+    const MPU_RBAR_VALID: u32 = 1 << 4;
+    const MPU_RBAR_REGION_MASK: u32 = 0xF;
+    const MPU_RASR_ENABLE: u32 = 1 << 0;
+
+    /// Access permissions of an ARMv7-M MPU region (`AP` field)
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[repr(u8)]
+    pub enum AccessPermission {
+        /// No access
+        NoAccess = 0b000,
+        /// Privileged read-write, no unprivileged access
+        PrivilegedReadWrite = 0b001,
+        /// Privileged read-write, unprivileged read-only
+        UnprivilegedReadOnly = 0b010,
+        /// Full access
+        ReadWrite = 0b011,
+        /// Privileged read-only, no unprivileged access
+        PrivilegedReadOnly = 0b101,
+        /// Read-only for both privilege levels
+        ReadOnly = 0b110,
+    }
+
+    /// Memory type and cacheability of an ARMv7-M MPU region (`TEX`, `C`, `B` and `S` fields)
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct MemoryAttributes {
+        tex: u8,
+        c: bool,
+        b: bool,
+        s: bool,
+    }
+
+    impl MemoryAttributes {
+        /// Strongly-ordered memory, always shareable
+        pub const STRONGLY_ORDERED: Self = MemoryAttributes {
+            tex: 0b000,
+            c: false,
+            b: false,
+            s: true,
+        };
+
+        /// Shareable device memory, for peripherals
+        pub const DEVICE: Self = MemoryAttributes {
+            tex: 0b000,
+            c: false,
+            b: true,
+            s: true,
+        };
+
+        /// Normal memory, write-back and write-allocate cacheable, not shareable
+        pub const NORMAL_WBWA: Self = MemoryAttributes {
+            tex: 0b001,
+            c: true,
+            b: true,
+            s: false,
+        };
+
+        /// Normal memory, write-through cacheable without write allocation, not shareable
+        pub const NORMAL_WRITE_THROUGH: Self = MemoryAttributes {
+            tex: 0b000,
+            c: true,
+            b: false,
+            s: false,
+        };
+
+        /// Normal memory, not cacheable, not shareable
+        pub const NORMAL_NON_CACHEABLE: Self = MemoryAttributes {
+            tex: 0b001,
+            c: false,
+            b: false,
+            s: false,
+        };
+
+        /// Creates memory attributes from the raw `TEX`, `C`, `B` and `S` fields.
+        ///
+        /// Returns [`Error::InvalidFieldValue`] if `tex` does not fit in 3 bits.
+        #[inline]
+        pub const fn from_fields(tex: u8, c: bool, b: bool, s: bool) -> Result<Self> {
+            if tex > 0b111 {
+                return Err(Error::InvalidFieldValue {
+                    field: "TEX",
+                    value: tex as usize,
+                    bitmask: 0b111,
+                });
+            }
+            Ok(MemoryAttributes { tex, c, b, s })
+        }
+
+        /// Returns the same attributes with the shareable bit set to `shareable`.
+        ///
+        /// The bit is ignored for strongly-ordered and device memory.
+        #[inline]
+        pub const fn shareable(mut self, shareable: bool) -> Self {
+            self.s = shareable;
+            self
+        }
+
+        #[inline(always)]
+        const fn rasr_bits(self) -> u32 {
+            ((self.tex as u32) << 19)
+                | ((self.s as u32) << 18)
+                | ((self.c as u32) << 17)
+                | ((self.b as u32) << 16)
+        }
+    }
+
+    /// An ARMv7-M MPU region
+    ///
+    /// The region defaults to read-write for both privilege levels, executable, normal
+    /// write-back write-allocate memory, with all its subregions enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use cortex_m::peripheral::mpu::{AccessPermission, MemoryAttributes, MpuOptions, MpuRegion};
+    /// use cortex_m::peripheral::Peripherals;
+    ///
+    /// let flash = MpuRegion::new(0x0800_0000, 1 << 20)
+    ///     .unwrap()
+    ///     .access(AccessPermission::ReadOnly);
+    /// let sram = MpuRegion::new(0x2000_0000, 128 << 10)
+    ///     .unwrap()
+    ///     .execute_never(true);
+    /// let peripherals = MpuRegion::new(0x4000_0000, 512 << 20)
+    ///     .unwrap()
+    ///     .attributes(MemoryAttributes::DEVICE)
+    ///     .execute_never(true);
+    ///
+    /// let mut p = Peripherals::take().unwrap();
+    /// p.MPU.set_regions(0, &[flash, sram, peripherals]).unwrap();
+    /// p.MPU.enable(MpuOptions {
+    ///     privileged_default_map: true,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct MpuRegion {
+        base: u32,
+        size_log2: u8,
+        subregions_disabled: u8,
+        access: AccessPermission,
+        execute_never: bool,
+        attributes: MemoryAttributes,
+    }
+
+    impl MpuRegion {
+        /// Creates a region of `size` bytes at `base`.
+        ///
+        /// `size` must be a power of two of at least 32 bytes, and `base` must be aligned on
+        /// `size`. Returns [`Error::InvalidValue`] for an invalid size, with the bitmask of the
+        /// smallest size, and [`Error::InvalidFieldValue`] for the `ADDR` field if `base` is not
+        /// aligned, with the bitmask of the bits that may be set.
+        #[inline]
+        pub const fn new(base: u32, size: u32) -> Result<Self> {
+            if !size.is_power_of_two() || size < 32 {
+                return Err(Error::InvalidValue {
+                    value: size as usize,
+                    bitmask: !0x1F_u32 as usize,
+                });
+            }
+            if base & (size - 1) != 0 {
+                return Err(Error::InvalidFieldValue {
+                    field: "ADDR",
+                    value: base as usize,
+                    bitmask: !(size - 1) as usize,
+                });
+            }
+            Ok(MpuRegion {
+                base,
+                size_log2: size.trailing_zeros() as u8,
+                subregions_disabled: 0,
+                access: AccessPermission::ReadWrite,
+                execute_never: false,
+                attributes: MemoryAttributes::NORMAL_WBWA,
+            })
+        }
+
+        /// Sets the access permissions.
+        #[inline]
+        pub const fn access(mut self, access: AccessPermission) -> Self {
+            self.access = access;
+            self
+        }
+
+        /// Sets whether instruction fetches from the region fault.
+        #[inline]
+        pub const fn execute_never(mut self, execute_never: bool) -> Self {
+            self.execute_never = execute_never;
+            self
+        }
+
+        /// Sets the memory attributes.
+        #[inline]
+        pub const fn attributes(mut self, attributes: MemoryAttributes) -> Self {
+            self.attributes = attributes;
+            self
+        }
+
+        /// Disables the subregions whose bits are set in `mask`.
+        ///
+        /// The region is split into 8 subregions of equal size, bit `n` of `mask` disabling
+        /// subregion `n`. Returns [`Error::InvalidFieldValue`] for the `SRD` field if `mask` is not
+        /// zero and the region is smaller than 256 bytes, which does not support subregions.
+        #[inline]
+        pub const fn disable_subregions(mut self, mask: u8) -> Result<Self> {
+            if mask != 0 && self.size_log2 < 8 {
+                return Err(Error::InvalidFieldValue {
+                    field: "SRD",
+                    value: mask as usize,
+                    bitmask: 0,
+                });
+            }
+            self.subregions_disabled = mask;
+            Ok(self)
+        }
+
+        /// Returns the base address.
+        #[inline]
+        pub const fn base(&self) -> u32 {
+            self.base
+        }
+
+        /// Returns the size in bytes.
+        #[inline]
+        pub const fn size(&self) -> u32 {
+            1 << self.size_log2
+        }
+
+        /// Returns the value of the `RBAR` register that selects region `number` and sets its
+        /// base address.
+        #[inline]
+        pub const fn rbar(&self, number: u8) -> u32 {
+            self.base | MPU_RBAR_VALID | (number as u32 & MPU_RBAR_REGION_MASK)
+        }
+
+        /// Returns the value of the `RASR` register of the region, with the region enabled.
+        #[inline]
+        pub const fn rasr(&self) -> u32 {
+            ((self.execute_never as u32) << 28)
+                | ((self.access as u32) << 24)
+                | self.attributes.rasr_bits()
+                | ((self.subregions_disabled as u32) << 8)
+                | (((self.size_log2 - 1) as u32) << 1)
+                | MPU_RASR_ENABLE
+        }
+    }
+
+    impl MPU {
+        /// Programs region `number`.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if the region is not implemented.
+        #[inline]
+        pub fn set_region(&mut self, number: u8, region: MpuRegion) -> Result<()> {
+            self.check_regions(number, 1)?;
+            // NOTE(unsafe) we have exclusive access through `&mut self`; the VALID bit of RBAR
+            // selects the region
+            unsafe {
+                self.rbar.write(region.rbar(number));
+                self.rasr.write(region.rasr());
+            }
+            Ok(())
+        }
+
+        /// Disables region `number`.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if the region is not implemented.
+        #[inline]
+        pub fn disable_region(&mut self, number: u8) -> Result<()> {
+            self.check_regions(number, 1)?;
+            // NOTE(unsafe) we have exclusive access through `&mut self`
+            unsafe {
+                self.rnr.write(u32::from(number));
+                self.rasr.write(0);
+            }
+            Ok(())
+        }
+
+        /// Programs `regions` into consecutive regions starting at `first`.
+        ///
+        /// The regions are written four at a time through the `RBAR`/`RASR` alias registers.
+        /// Returns [`Error::IndexOutOfBounds`] if any of the regions is not implemented, in which
+        /// case no region is written.
+        #[inline]
+        pub fn set_regions(&mut self, first: u8, regions: &[MpuRegion]) -> Result<()> {
+            let count = u8::try_from(regions.len()).map_err(|_| Error::IndexOutOfBounds {
+                index: usize::from(first) + regions.len() - 1,
+                min: 0,
+                max: usize::from(self.region_count()).saturating_sub(1),
+            })?;
+            self.check_regions(first, count)?;
+
+            for (burst, chunk) in regions.chunks(4).enumerate() {
+                let number = first + 4 * burst as u8;
+                let aliases = [
+                    (&self.rbar, &self.rasr),
+                    (&self.rbar_a1, &self.rasr_a1),
+                    (&self.rbar_a2, &self.rasr_a2),
+                    (&self.rbar_a3, &self.rasr_a3),
+                ];
+                for (i, (region, (rbar, rasr))) in chunk.iter().zip(aliases).enumerate() {
+                    // NOTE(unsafe) we have exclusive access through `&mut self`; the VALID bit of
+                    // RBAR selects the region
+                    unsafe {
+                        rbar.write(region.rbar(number + i as u8));
+                        rasr.write(region.rasr());
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Checks that the `count` regions starting at `first` are implemented.
+        #[inline]
+        fn check_regions(&self, first: u8, count: u8) -> Result<()> {
+            check_regions(first, count, self.region_count())
+        }
+    }
+
+    /// Checks that the `count` regions starting at `first` are among the `implemented` ones, and
+    /// can be selected by `RBAR`. No region is checked if `count` is `0`.
+    #[inline]
+    pub(crate) fn check_regions(first: u8, count: u8, implemented: u8) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        let implemented = usize::from(implemented);
+        let last = usize::from(first) + usize::from(count) - 1;
+        if last >= implemented || last > MPU_RBAR_REGION_MASK as usize {
+            Err(Error::IndexOutOfBounds {
+                index: last,
+                min: 0,
+                max: implemented.saturating_sub(1),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(armv8m)]
+pub use self::v8::*;
+
+// The encoding of the attributes and regions is also built for the host tests, where the names
+// of the types are not part of the exported API
+#[cfg(any(armv8m, test))]
+#[cfg_attr(test, allow(clippy::upper_case_acronyms, clippy::enum_variant_names))]
+mod v8 {
+    use cortex_m_types::result::{Error, Result};
+    #[cfg(feature = "serde")]
+    use serde::{Deserialize, Serialize};
+
+    #[cfg(armv8m)]
+    use crate::peripheral::MPU;
+
+    /// Bits of the region addresses below the 32-byte region granularity
+    const MPU_ADDRESS_GRANULE_MASK: u32 = 0x1F;
+    const MPU_RLAR_PXN: u32 = 1 << 4;
+    const MPU_RLAR_ENABLE: u32 = 1 << 0;
+    /// Number of attributes held by the MAIR registers
+    const MPU_MAIR_ATTRIBUTES: u8 = 8;
+
+    /// Device memory type of a MAIR attribute
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[repr(u8)]
+    pub enum DeviceMemory {
+        /// Non-gathering, non-reordering, no early write acknowledgement
+        NGnRnE = 0b00,
+        /// Non-gathering, non-reordering, early write acknowledgement
+        NGnRE = 0b01,
+        /// Non-gathering, reordering, early write acknowledgement
+        NGRE = 0b10,
+        /// Gathering, reordering, early write acknowledgement
+        GRE = 0b11,
+    }
+
+    /// Cacheability of normal memory, for the inner or the outer domain of a MAIR attribute
+    ///
+    /// The cacheable policies are non-transient.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub enum Cacheability {
+        /// Not cacheable
+        NonCacheable,
+        /// Write-through cacheable
+        WriteThrough {
+            /// Allocate on read misses
+            read_allocate: bool,
+            /// Allocate on write misses
+            write_allocate: bool,
+        },
+        /// Write-back cacheable
+        WriteBack {
+            /// Allocate on read misses
+            read_allocate: bool,
+            /// Allocate on write misses
+            write_allocate: bool,
+        },
+    }
+
+    impl Cacheability {
+        #[inline(always)]
+        const fn bits(self) -> u8 {
+            match self {
+                Cacheability::NonCacheable => 0b0100,
+                Cacheability::WriteThrough {
+                    read_allocate,
+                    write_allocate,
+                } => 0b1000 | ((read_allocate as u8) << 1) | write_allocate as u8,
+                Cacheability::WriteBack {
+                    read_allocate,
+                    write_allocate,
+                } => 0b1100 | ((read_allocate as u8) << 1) | write_allocate as u8,
+            }
+        }
+    }
+
+    /// A memory attribute, as held by the MAIR registers and referenced by the regions
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub enum MemoryAttribute {
+        /// Device memory
+        Device(DeviceMemory),
+        /// Normal memory
+        Normal {
+            /// Outer cacheability
+            outer: Cacheability,
+            /// Inner cacheability
+            inner: Cacheability,
+        },
+    }
+
+    impl MemoryAttribute {
+        /// Device-nGnRnE memory, the most restrictive type, for peripherals with side effects
+        pub const DEVICE_NGNRNE: Self = MemoryAttribute::Device(DeviceMemory::NGnRnE);
+
+        /// Device-nGnRE memory, for most peripherals
+        pub const DEVICE_NGNRE: Self = MemoryAttribute::Device(DeviceMemory::NGnRE);
+
+        /// Normal memory, write-back read- and write-allocate cacheable
+        pub const NORMAL_WBWA: Self = MemoryAttribute::Normal {
+            outer: Cacheability::WriteBack {
+                read_allocate: true,
+                write_allocate: true,
+            },
+            inner: Cacheability::WriteBack {
+                read_allocate: true,
+                write_allocate: true,
+            },
+        };
+
+        /// Normal memory, write-through read-allocate cacheable
+        pub const NORMAL_WRITE_THROUGH: Self = MemoryAttribute::Normal {
+            outer: Cacheability::WriteThrough {
+                read_allocate: true,
+                write_allocate: false,
+            },
+            inner: Cacheability::WriteThrough {
+                read_allocate: true,
+                write_allocate: false,
+            },
+        };
+
+        /// Normal memory, not cacheable
+        pub const NORMAL_NON_CACHEABLE: Self = MemoryAttribute::Normal {
+            outer: Cacheability::NonCacheable,
+            inner: Cacheability::NonCacheable,
+        };
+
+        /// Returns the 8-bit encoding of the attribute in the MAIR registers.
+        #[inline]
+        pub const fn bits(self) -> u8 {
+            match self {
+                MemoryAttribute::Device(device) => (device as u8) << 2,
+                MemoryAttribute::Normal { outer, inner } => (outer.bits() << 4) | inner.bits(),
+            }
+        }
+    }
+
+    /// Shareability of an ARMv8-M MPU region (`SH` field)
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[repr(u8)]
+    pub enum Shareability {
+        /// Non-shareable
+        NonShareable = 0b00,
+        /// Outer shareable
+        OuterShareable = 0b10,
+        /// Inner shareable
+        InnerShareable = 0b11,
+    }
+
+    /// Access permissions of an ARMv8-M MPU region (`AP` field)
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[repr(u8)]
+    pub enum AccessPermission {
+        /// Privileged read-write, no unprivileged access
+        PrivilegedReadWrite = 0b00,
+        /// Read-write for both privilege levels
+        ReadWrite = 0b01,
+        /// Privileged read-only, no unprivileged access
+        PrivilegedReadOnly = 0b10,
+        /// Read-only for both privilege levels
+        ReadOnly = 0b11,
+    }
+
+    /// An ARMv8-M MPU region
+    ///
+    /// The region defaults to read-write for both privilege levels, executable, non-shareable,
+    /// and uses the memory attribute at index 0 of the MAIR registers.
+    ///
+    /// # Example
+    ///
+    /// ``` ignore
+    /// use cortex_m::peripheral::mpu::{AccessPermission, MemoryAttribute, MpuOptions, MpuRegion};
+    /// use cortex_m::peripheral::Peripherals;
+    ///
+    /// let mut p = Peripherals::take().unwrap();
+    /// p.MPU.set_attribute(0, MemoryAttribute::NORMAL_WBWA).unwrap();
+    /// p.MPU.set_attribute(1, MemoryAttribute::DEVICE_NGNRE).unwrap();
+    ///
+    /// let flash = MpuRegion::new(0x0800_0000, 0x080F_FFFF)
+    ///     .unwrap()
+    ///     .access(AccessPermission::ReadOnly);
+    /// let sram = MpuRegion::new(0x2000_0000, 0x2001_FFFF)
+    ///     .unwrap()
+    ///     .execute_never(true);
+    /// let peripherals = MpuRegion::new(0x4000_0000, 0x5FFF_FFFF)
+    ///     .unwrap()
+    ///     .attribute_index(1)
+    ///     .unwrap()
+    ///     .execute_never(true);
+    ///
+    /// p.MPU.set_regions(0, &[flash, sram, peripherals]).unwrap();
+    /// p.MPU.enable(MpuOptions {
+    ///     privileged_default_map: true,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct MpuRegion {
+        base: u32,
+        limit: u32,
+        shareability: Shareability,
+        access: AccessPermission,
+        execute_never: bool,
+        privileged_execute_never: bool,
+        attribute_index: u8,
+    }
+
+    impl MpuRegion {
+        /// Creates a region from its first address `base` to its last address `limit`.
+        ///
+        /// Returns [`Error::InvalidFieldValue`] for the `BASE` field if `base` is not 32 bytes
+        /// aligned, with the bitmask of the bits that may be set, for the `LIMIT` field if the
+        /// 5 least significant bits of `limit` are not set, with the bitmask of the bits that must
+        /// be set, or [`Error::InvalidValue`] if `limit` is below `base`.
+        #[inline]
+        pub const fn new(base: u32, limit: u32) -> Result<Self> {
+            if base & MPU_ADDRESS_GRANULE_MASK != 0 {
+                return Err(Error::InvalidFieldValue {
+                    field: "BASE",
+                    value: base as usize,
+                    bitmask: !MPU_ADDRESS_GRANULE_MASK as usize,
+                });
+            }
+            if limit & MPU_ADDRESS_GRANULE_MASK != MPU_ADDRESS_GRANULE_MASK {
+                return Err(Error::InvalidFieldValue {
+                    field: "LIMIT",
+                    value: limit as usize,
+                    bitmask: MPU_ADDRESS_GRANULE_MASK as usize,
+                });
+            }
+            if limit < base {
+                return Err(Error::InvalidValue {
+                    value: limit as usize,
+                    bitmask: !MPU_ADDRESS_GRANULE_MASK as usize,
+                });
+            }
+            Ok(MpuRegion {
+                base,
+                limit,
+                shareability: Shareability::NonShareable,
+                access: AccessPermission::ReadWrite,
+                execute_never: false,
+                privileged_execute_never: false,
+                attribute_index: 0,
+            })
+        }
+
+        /// Sets the access permissions.
+        #[inline]
+        pub const fn access(mut self, access: AccessPermission) -> Self {
+            self.access = access;
+            self
+        }
+
+        /// Sets the shareability.
+        #[inline]
+        pub const fn shareability(mut self, shareability: Shareability) -> Self {
+            self.shareability = shareability;
+            self
+        }
+
+        /// Sets whether instruction fetches from the region fault.
+        #[inline]
+        pub const fn execute_never(mut self, execute_never: bool) -> Self {
+            self.execute_never = execute_never;
+            self
+        }
+
+        /// Sets whether privileged instruction fetches from the region fault (`PXN`).
+        ///
+        /// `PXN` was introduced by ARMv8.1-M, and is ignored by ARMv8.0-M cores.
+        #[inline]
+        pub const fn privileged_execute_never(mut self, privileged_execute_never: bool) -> Self {
+            self.privileged_execute_never = privileged_execute_never;
+            self
+        }
+
+        /// Sets the index of the memory attribute of the region in the MAIR registers.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if `index` is greater than 7.
+        #[inline]
+        pub const fn attribute_index(mut self, index: u8) -> Result<Self> {
+            if index >= MPU_MAIR_ATTRIBUTES {
+                return Err(Error::IndexOutOfBounds {
+                    index: index as usize,
+                    min: 0,
+                    max: MPU_MAIR_ATTRIBUTES as usize - 1,
+                });
+            }
+            self.attribute_index = index;
+            Ok(self)
+        }
+
+        /// Returns the first address of the region.
+        #[inline]
+        pub const fn base(&self) -> u32 {
+            self.base
+        }
+
+        /// Returns the last address of the region.
+        #[inline]
+        pub const fn limit(&self) -> u32 {
+            self.limit
+        }
+
+        /// Returns `true` if the region shares at least one address with `other`.
+        #[inline]
+        pub const fn overlaps(&self, other: &MpuRegion) -> bool {
+            self.base <= other.limit && other.base <= self.limit
+        }
+
+        /// Returns the value of the `RBAR` register of the region.
+        #[inline]
+        pub const fn rbar(&self) -> u32 {
+            self.base
+                | ((self.shareability as u32) << 3)
+                | ((self.access as u32) << 1)
+                | self.execute_never as u32
+        }
+
+        /// Returns the value of the `RLAR` register of the region, with the region enabled.
+        #[inline]
+        pub const fn rlar(&self) -> u32 {
+            let pxn = if self.privileged_execute_never {
+                MPU_RLAR_PXN
+            } else {
+                0
+            };
+            (self.limit & !MPU_ADDRESS_GRANULE_MASK)
+                | pxn
+                | ((self.attribute_index as u32) << 1)
+                | MPU_RLAR_ENABLE
+        }
+
+        /// Decodes the values of the `RBAR` and `RLAR` registers, or returns `None` if the
+        /// region is disabled.
+        #[inline]
+        pub const fn from_registers(rbar: u32, rlar: u32) -> Option<Self> {
+            if rlar & MPU_RLAR_ENABLE == 0 {
+                return None;
+            }
+            let shareability = match (rbar >> 3) & 0b11 {
+                0b10 => Shareability::OuterShareable,
+                0b11 => Shareability::InnerShareable,
+                _ => Shareability::NonShareable,
+            };
+            let access = match (rbar >> 1) & 0b11 {
+                0b00 => AccessPermission::PrivilegedReadWrite,
+                0b01 => AccessPermission::ReadWrite,
+                0b10 => AccessPermission::PrivilegedReadOnly,
+                _ => AccessPermission::ReadOnly,
+            };
+            Some(MpuRegion {
+                base: rbar & !MPU_ADDRESS_GRANULE_MASK,
+                limit: rlar | MPU_ADDRESS_GRANULE_MASK,
+                shareability,
+                access,
+                execute_never: rbar & 1 != 0,
+                privileged_execute_never: rlar & MPU_RLAR_PXN != 0,
+                attribute_index: ((rlar >> 1) & 0b111) as u8,
+            })
+        }
+    }
+
+    #[cfg(armv8m)]
+    impl MPU {
+        /// Sets the memory attribute at `index` of the MAIR registers.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if `index` is greater than 7.
+        #[inline]
+        pub fn set_attribute(&mut self, index: u8, attribute: MemoryAttribute) -> Result<()> {
+            if index >= MPU_MAIR_ATTRIBUTES {
+                return Err(Error::IndexOutOfBounds {
+                    index: usize::from(index),
+                    min: 0,
+                    max: usize::from(MPU_MAIR_ATTRIBUTES) - 1,
+                });
+            }
+            let shift = (index % 4) * 8;
+            // NOTE(unsafe) we have exclusive access through `&mut self`
+            unsafe {
+                self.mair[usize::from(index / 4)].modify(|mair| {
+                    (mair & !(0xFF << shift)) | (u32::from(attribute.bits()) << shift)
+                });
+            }
+            Ok(())
+        }
+
+        /// Returns region `number`, or `None` if it is disabled.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if the region is not implemented.
+        #[inline]
+        pub fn get_region(&mut self, number: u8) -> Result<Option<MpuRegion>> {
+            self.check_regions(number, 1)?;
+            // NOTE(unsafe) we have exclusive access through `&mut self`
+            unsafe { self.rnr.write(u32::from(number)) };
+            Ok(MpuRegion::from_registers(
+                self.rbar.read(),
+                self.rlar.read(),
+            ))
+        }
+
+        /// Programs region `number`.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if the region is not implemented, or
+        /// [`Error::RegionOverlap`] if it overlaps another enabled region, as accesses to
+        /// overlapping regions fault.
+        #[inline]
+        pub fn set_region(&mut self, number: u8, region: MpuRegion) -> Result<()> {
+            self.set_regions(number, &[region])
+        }
+
+        /// Disables region `number`.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if the region is not implemented.
+        #[inline]
+        pub fn disable_region(&mut self, number: u8) -> Result<()> {
+            self.check_regions(number, 1)?;
+            // NOTE(unsafe) we have exclusive access through `&mut self`
+            unsafe {
+                self.rnr.write(u32::from(number));
+                self.rlar.write(0);
+            }
+            Ok(())
+        }
+
+        /// Programs `regions` into consecutive regions starting at `first`.
+        ///
+        /// Returns [`Error::IndexOutOfBounds`] if any of the regions is not implemented, or
+        /// [`Error::RegionOverlap`] if two of the regions overlap, or if one of them overlaps an
+        /// enabled region outside of the programmed ones. No region is written in case of error.
+        ///
+        /// The regions are written through the `RBAR`/`RLAR` alias registers, up to four per
+        /// write of `RNR`.
+        #[inline]
+        pub fn set_regions(&mut self, first: u8, regions: &[MpuRegion]) -> Result<()> {
+            let count = u8::try_from(regions.len()).map_err(|_| Error::IndexOutOfBounds {
+                index: usize::from(first) + regions.len() - 1,
+                min: 0,
+                max: usize::from(self.region_count()).saturating_sub(1),
+            })?;
+            self.check_regions(first, count)?;
+
+            for (i, region) in regions.iter().enumerate() {
+                if let Some(j) = regions[i + 1..]
+                    .iter()
+                    .position(|other| region.overlaps(other))
+                {
+                    return Err(Error::RegionOverlap {
+                        region: usize::from(first) + i,
+                        other: usize::from(first) + i + 1 + j,
+                    });
+                }
+            }
+            let programmed = usize::from(first)..usize::from(first) + regions.len();
+            for number in 0..self.region_count() {
+                if programmed.contains(&usize::from(number)) {
+                    continue;
+                }
+                let Some(enabled) = self.get_region(number)? else {
+                    continue;
+                };
+                if let Some(i) = regions.iter().position(|region| region.overlaps(&enabled)) {
+                    return Err(Error::RegionOverlap {
+                        region: usize::from(first) + i,
+                        other: usize::from(number),
+                    });
+                }
+            }
+
+            let aliases = [
+                (&self.rbar, &self.rlar),
+                (&self.rbar_a1, &self.rlar_a1),
+                (&self.rbar_a2, &self.rlar_a2),
+                (&self.rbar_a3, &self.rlar_a3),
+            ];
+            for (i, region) in regions.iter().enumerate() {
+                let number = first + i as u8;
+                let (rbar, rlar) = aliases[usize::from(number % 4)];
+                // NOTE(unsafe) we have exclusive access through `&mut self`; the alias registers
+                // access the regions following the multiple of four selected by RNR
+                unsafe {
+                    if i == 0 || number % 4 == 0 {
+                        self.rnr.write(u32::from(number & !3));
+                    }
+                    rbar.write(region.rbar());
+                    rlar.write(region.rlar());
+                }
+            }
+            Ok(())
+        }
+
+        /// Checks that the `count` regions starting at `first` are implemented.
+        #[inline]
+        fn check_regions(&self, first: u8, count: u8) -> Result<()> {
+            check_regions(first, count, self.region_count())
+        }
+    }
+
+    /// Checks that the `count` regions starting at `first` are among the `implemented` ones. No
+    /// region is checked if `count` is `0`.
+    #[inline]
+    pub(crate) fn check_regions(first: u8, count: u8, implemented: u8) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        let implemented = usize::from(implemented);
+        let last = usize::from(first) + usize::from(count) - 1;
+        if last >= implemented {
+            Err(Error::IndexOutOfBounds {
+                index: last,
+                min: 0,
+                max: implemented.saturating_sub(1),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn memory_attribute_bits() {
+            assert_eq!(MemoryAttribute::Device(DeviceMemory::NGnRnE).bits(), 0x00);
+            assert_eq!(MemoryAttribute::Device(DeviceMemory::NGnRE).bits(), 0x04);
+            assert_eq!(MemoryAttribute::Device(DeviceMemory::NGRE).bits(), 0x08);
+            assert_eq!(MemoryAttribute::Device(DeviceMemory::GRE).bits(), 0x0C);
+            assert_eq!(MemoryAttribute::DEVICE_NGNRNE.bits(), 0x00);
+            assert_eq!(MemoryAttribute::DEVICE_NGNRE.bits(), 0x04);
+
+            assert_eq!(MemoryAttribute::NORMAL_WBWA.bits(), 0xFF);
+            assert_eq!(MemoryAttribute::NORMAL_WRITE_THROUGH.bits(), 0xAA);
+            assert_eq!(MemoryAttribute::NORMAL_NON_CACHEABLE.bits(), 0x44);
+
+            // outer in the high nibble, inner in the low one
+            let attribute = MemoryAttribute::Normal {
+                outer: Cacheability::WriteBack {
+                    read_allocate: true,
+                    write_allocate: false,
+                },
+                inner: Cacheability::WriteThrough {
+                    read_allocate: false,
+                    write_allocate: true,
+                },
+            };
+            assert_eq!(attribute.bits(), 0xE9);
+            let attribute = MemoryAttribute::Normal {
+                outer: Cacheability::NonCacheable,
+                inner: Cacheability::WriteBack {
+                    read_allocate: false,
+                    write_allocate: false,
+                },
+            };
+            assert_eq!(attribute.bits(), 0x4C);
+        }
+
+        #[test]
+        fn region_registers() {
+            let region = MpuRegion::new(0x2000_0000, 0x2001_FFFF).unwrap();
+            assert_eq!(region.base(), 0x2000_0000);
+            assert_eq!(region.limit(), 0x2001_FFFF);
+            assert_eq!(region.rbar(), 0x2000_0002);
+            assert_eq!(region.rlar(), 0x2001_FFE1);
+
+            let region = region
+                .shareability(Shareability::InnerShareable)
+                .access(AccessPermission::ReadOnly)
+                .execute_never(true)
+                .privileged_execute_never(true)
+                .attribute_index(5)
+                .unwrap();
+            assert_eq!(region.rbar(), 0x2000_001F);
+            assert_eq!(region.rlar(), 0x2001_FFFB);
+
+            let region = region
+                .shareability(Shareability::OuterShareable)
+                .access(AccessPermission::PrivilegedReadOnly)
+                .privileged_execute_never(false);
+            assert_eq!(region.rbar(), 0x2000_0015);
+            assert_eq!(region.rlar(), 0x2001_FFEB);
+
+            assert_eq!(
+                region.attribute_index(8),
+                Err(Error::IndexOutOfBounds {
+                    index: 8,
+                    min: 0,
+                    max: 7
+                })
+            );
+        }
+
+        #[test]
+        fn region_from_registers() {
+            let base = MpuRegion::new(0x0800_0000, 0x080F_FFFF).unwrap();
+            let regions = [
+                base,
+                base.access(AccessPermission::PrivilegedReadWrite)
+                    .shareability(Shareability::OuterShareable),
+                base.access(AccessPermission::ReadOnly)
+                    .execute_never(true)
+                    .attribute_index(7)
+                    .unwrap(),
+                base.privileged_execute_never(true)
+                    .shareability(Shareability::InnerShareable),
+            ];
+            for region in regions {
+                assert_eq!(
+                    MpuRegion::from_registers(region.rbar(), region.rlar()),
+                    Some(region)
+                );
+            }
+
+            // disabled
+            assert_eq!(
+                MpuRegion::from_registers(base.rbar(), base.rlar() & !1),
+                None
+            );
+        }
+
+        #[test]
+        fn region_overlaps() {
+            let first = MpuRegion::new(0x0000_0000, 0x0000_001F).unwrap();
+            let second = MpuRegion::new(0x0000_0020, 0x0000_003F).unwrap();
+            let both = MpuRegion::new(0x0000_0000, 0x0000_00FF).unwrap();
+            let all = MpuRegion::new(0x0000_0000, 0xFFFF_FFFF).unwrap();
+            let last = MpuRegion::new(0xFFFF_FFE0, 0xFFFF_FFFF).unwrap();
+
+            // adjacent regions do not overlap
+            assert!(!first.overlaps(&second));
+            assert!(!second.overlaps(&first));
+
+            assert!(first.overlaps(&first));
+            assert!(both.overlaps(&second));
+            assert!(second.overlaps(&both));
+            assert!(all.overlaps(&last));
+            assert!(last.overlaps(&all));
+            assert!(!last.overlaps(&both));
+        }
+
+        #[test]
+        fn check_regions_range() {
+            assert_eq!(check_regions(0, 8, 8), Ok(()));
+            assert_eq!(check_regions(7, 1, 8), Ok(()));
+            assert_eq!(
+                check_regions(7, 2, 8),
+                Err(Error::IndexOutOfBounds {
+                    index: 8,
+                    min: 0,
+                    max: 7
+                })
+            );
+            // an empty range, for example past the top region
+            assert_eq!(check_regions(8, 0, 8), Ok(()));
+            assert_eq!(check_regions(255, 0, 0), Ok(()));
+        }
+
+        #[test]
+        fn region_new() {
+            assert_eq!(
+                MpuRegion::new(0x2000_0010, 0x2000_003F),
+                Err(Error::InvalidFieldValue {
+                    field: "BASE",
+                    value: 0x2000_0010,
+                    bitmask: 0xFFFF_FFE0
+                })
+            );
+            assert_eq!(
+                MpuRegion::new(0x2000_0000, 0x2000_0020),
+                Err(Error::InvalidFieldValue {
+                    field: "LIMIT",
+                    value: 0x2000_0020,
+                    bitmask: 0x1F
+                })
+            );
+            assert_eq!(
+                MpuRegion::new(0x2000_0040, 0x2000_001F),
+                Err(Error::InvalidValue {
+                    value: 0x2000_001F,
+                    bitmask: 0xFFFF_FFE0
+                })
+            );
+            // a single 32-byte granule
+            assert!(MpuRegion::new(0x2000_0000, 0x2000_001F).is_ok());
+        }
+    }
+}
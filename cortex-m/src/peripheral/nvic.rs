@@ -1,11 +1,28 @@
 //! Nested Vector Interrupt Controller
 
+use core::fmt;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+
 use volatile_register::RW;
 #[cfg(not(armv6m))]
 use volatile_register::{RO, WO};
 
-use crate::interrupt::InterruptNumber;
+use crate::interrupt::{InterruptNumber, PriorityNumber};
 use crate::peripheral::NVIC;
+use crate::priority::{HwPriority, LogicalPriority};
+use cortex_m_types::result::{Error, Result};
+
+/// Number of external interrupts supported by the NVIC
+#[cfg(not(armv6m))]
+pub(crate) const NVIC_MAX_INTERRUPTS: usize = 496;
+
+/// Number of external interrupts supported by the NVIC
+#[cfg(armv6m)]
+pub(crate) const NVIC_MAX_INTERRUPTS: usize = 32;
+
+/// Number of 32-bit words needed to hold one bit per external interrupt
+pub(crate) const NVIC_WORDS: usize = NVIC_MAX_INTERRUPTS.div_ceil(32);
 
 /// Register block
 #[repr(C)]
@@ -98,10 +115,10 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
 
         unsafe {
-            self.stir.write(u32::from(nr));
+            self.stir.write(nr as u32);
         }
     }
 
@@ -111,9 +128,9 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
         // NOTE(unsafe) this is a write to a stateless register
-        unsafe { (*Self::PTR).icer[usize::from(nr / 32)].write(1 << (nr % 32)) }
+        unsafe { (*Self::PTR).icer[nr / 32].write(1 << (nr % 32)) }
     }
 
     /// Enables `interrupt`
@@ -124,35 +141,64 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
         // NOTE(ptr) this is a write to a stateless register
-        (*Self::PTR).iser[usize::from(nr / 32)].write(1 << (nr % 32))
+        (*Self::PTR).iser[nr / 32].write(1 << (nr % 32))
     }
 
     /// Returns the NVIC priority of `interrupt`
     ///
-    /// *NOTE* NVIC encodes priority in the highest bits of a byte so values like `1` and `2` map
-    /// to the same priority. Also for NVIC priorities, a lower value (e.g. `16`) has higher
-    /// priority (urgency) than a larger value (e.g. `32`).
+    /// The hardware encoding is converted back to the logical priority level `P` as described in
+    /// [`set_priority`](struct.NVIC.html#method.set_priority). An error is returned if the value
+    /// currently held in the register does not match any variant of `P`.
     #[inline]
-    pub fn get_priority<I>(interrupt: I) -> u8
+    pub fn get_priority<I, P>(interrupt: I) -> Result<P>
     where
         I: InterruptNumber,
+        P: PriorityNumber,
     {
-        #[cfg(not(armv6m))]
-        {
-            let nr = interrupt.number();
-            // NOTE(unsafe) atomic read with no side effects
-            unsafe { (*Self::PTR).ipr[usize::from(nr)].read() }
-        }
+        priority_from_hw(Self::hw_priority(interrupt))
+    }
 
-        #[cfg(armv6m)]
-        {
-            // NOTE(unsafe) atomic read with no side effects
-            let ipr_n = unsafe { (*Self::PTR).ipr[Self::ipr_index(interrupt)].read() };
-            let prio = (ipr_n >> Self::ipr_shift(interrupt)) & 0x0000_00ff;
-            prio as u8
-        }
+    /// Returns the priority of `interrupt` as a [`LogicalPriority`], for a device implementing
+    /// `bits` priority bits
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if `bits` is not between 1 and 8.
+    #[inline]
+    pub fn get_logical_priority<I>(interrupt: I, bits: u8) -> Result<LogicalPriority>
+    where
+        I: InterruptNumber,
+    {
+        LogicalPriority::from_hw(Self::hw_priority(interrupt), bits)
+    }
+
+    /// Returns the number of priority bits implemented by the device
+    ///
+    /// The bits are discovered by writing `0xFF` to the priority of interrupt 0 and reading back
+    /// which bits stuck. The previous priority is restored afterwards.
+    #[inline]
+    pub fn implemented_priority_bits(&mut self) -> u8 {
+        crate::interrupt::free(|_| {
+            #[cfg(not(armv6m))]
+            let hw = unsafe {
+                let prev = self.ipr[0].read();
+                self.ipr[0].write(0xFF);
+                let hw = self.ipr[0].read();
+                self.ipr[0].write(prev);
+                hw
+            };
+
+            #[cfg(armv6m)]
+            let hw = unsafe {
+                let prev = self.ipr[0].read();
+                self.ipr[0].write(prev | 0xFF);
+                let hw = self.ipr[0].read() as u8;
+                self.ipr[0].write(prev);
+                hw
+            };
+
+            hw.count_ones() as u8
+        })
     }
 
     /// Is `interrupt` active or pre-empted and stacked
@@ -162,11 +208,11 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
         let mask = 1 << (nr % 32);
 
         // NOTE(unsafe) atomic read with no side effects
-        unsafe { ((*Self::PTR).iabr[usize::from(nr / 32)].read() & mask) == mask }
+        unsafe { ((*Self::PTR).iabr[nr / 32].read() & mask) == mask }
     }
 
     /// Checks if `interrupt` is enabled
@@ -175,11 +221,11 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
         let mask = 1 << (nr % 32);
 
         // NOTE(unsafe) atomic read with no side effects
-        unsafe { ((*Self::PTR).iser[usize::from(nr / 32)].read() & mask) == mask }
+        unsafe { ((*Self::PTR).iser[nr / 32].read() & mask) == mask }
     }
 
     /// Checks if `interrupt` is pending
@@ -188,11 +234,11 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
         let mask = 1 << (nr % 32);
 
         // NOTE(unsafe) atomic read with no side effects
-        unsafe { ((*Self::PTR).ispr[usize::from(nr / 32)].read() & mask) == mask }
+        unsafe { ((*Self::PTR).ispr[nr / 32].read() & mask) == mask }
     }
 
     /// Forces `interrupt` into pending state
@@ -201,16 +247,22 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        let nr = Self::interrupt_number(interrupt);
 
         // NOTE(unsafe) atomic stateless write; ICPR doesn't store any state
-        unsafe { (*Self::PTR).ispr[usize::from(nr / 32)].write(1 << (nr % 32)) }
+        unsafe { (*Self::PTR).ispr[nr / 32].write(1 << (nr % 32)) }
     }
 
     /// Sets the "priority" of `interrupt` to `prio`
     ///
-    /// *NOTE* See [`get_priority`](struct.NVIC.html#method.get_priority) method for an explanation
-    /// of how NVIC priorities work.
+    /// `prio` is a logical priority level: following [`PriorityNumber`], a higher number means a
+    /// higher priority (urgency), and `P::MAX_PRIORITY_NUMBER` is the most urgent level. The NVIC
+    /// uses the opposite convention and only implements the highest bits of each priority byte,
+    /// so the level is inverted and shifted into the implemented bits before being written. The
+    /// number of implemented bits is taken to be the number of bits needed to represent
+    /// `P::MAX_PRIORITY_NUMBER`.
+    ///
+    /// `prio` can also be a [`LogicalPriority`], which carries the number of implemented bits.
     ///
     /// On ARMv6-M, updating an interrupt priority requires a read-modify-write operation. On
     /// ARMv7-M, the operation is performed in a single atomic write operation.
@@ -220,19 +272,128 @@ impl NVIC {
     /// Changing priority levels can break priority-based critical sections (see
     /// [`register::basepri`](crate::register::basepri)) and compromise memory safety.
     #[inline]
-    pub unsafe fn set_priority<I>(&mut self, interrupt: I, prio: u8)
+    pub unsafe fn set_priority<I, P>(&mut self, interrupt: I, prio: P)
+    where
+        I: InterruptNumber,
+        P: HwPriority,
+    {
+        Self::write_priority(interrupt, prio.to_hw())
+    }
+
+    /// Clears `interrupt`'s pending state
+    #[inline]
+    pub fn unpend<I>(interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = Self::interrupt_number(interrupt);
+
+        // NOTE(unsafe) atomic stateless write; ICPR doesn't store any state
+        unsafe { (*Self::PTR).icpr[nr / 32].write(1 << (nr % 32)) }
+    }
+
+    /// Disables all the interrupts in `set`
+    ///
+    /// Each ICER register is written at most once, so the interrupts sharing a register are
+    /// disabled at the same time.
+    #[inline]
+    pub fn mask_set<I>(set: &InterruptSet<I>)
+    where
+        I: InterruptNumber,
+    {
+        for (i, &word) in set.words().iter().enumerate() {
+            if word != 0 {
+                // NOTE(unsafe) this is a write to a stateless register
+                unsafe { (*Self::PTR).icer[i].write(word) }
+            }
+        }
+    }
+
+    /// Enables all the interrupts in `set`
+    ///
+    /// Each ISER register is written at most once, so the interrupts sharing a register are
+    /// enabled at the same time.
+    ///
+    /// This function is `unsafe` because it can break mask-based critical sections
+    #[inline]
+    pub unsafe fn unmask_set<I>(set: &InterruptSet<I>)
+    where
+        I: InterruptNumber,
+    {
+        for (i, &word) in set.words().iter().enumerate() {
+            if word != 0 {
+                // NOTE(ptr) this is a write to a stateless register
+                (*Self::PTR).iser[i].write(word)
+            }
+        }
+    }
+
+    /// Returns the set of enabled interrupts
+    ///
+    /// Together with [`NVIC::restore_enabled`] this can be used to save the enable state, for
+    /// example before entering a low-power mode.
+    #[inline]
+    pub fn enabled<I>() -> InterruptSet<I>
+    where
+        I: InterruptNumber,
+    {
+        // NOTE(unsafe) atomic reads with no side effects
+        InterruptSet::from_registers(|i| unsafe { (*Self::PTR).iser[i].read() })
+    }
+
+    /// Enables exactly the interrupts in `set`, and disables all the other interrupts of `I`
+    ///
+    /// This function is `unsafe` because it can break mask-based critical sections
+    #[inline]
+    pub unsafe fn restore_enabled<I>(set: &InterruptSet<I>)
+    where
+        I: InterruptNumber,
+    {
+        for (i, &word) in set.words().iter().enumerate() {
+            let mask = InterruptSet::<I>::word_mask(i);
+            (*Self::PTR).icer[i].write(!word & mask);
+            (*Self::PTR).iser[i].write(word & mask);
+        }
+    }
+
+    /// Returns the set of pending interrupts
+    #[inline]
+    pub fn pending<I>() -> InterruptSet<I>
+    where
+        I: InterruptNumber,
+    {
+        // NOTE(unsafe) atomic reads with no side effects
+        InterruptSet::from_registers(|i| unsafe { (*Self::PTR).ispr[i].read() })
+    }
+
+    /// Returns the set of active (or pre-empted and stacked) interrupts
+    #[cfg(not(armv6m))]
+    #[inline]
+    pub fn active<I>() -> InterruptSet<I>
+    where
+        I: InterruptNumber,
+    {
+        // NOTE(unsafe) atomic reads with no side effects
+        InterruptSet::from_registers(|i| unsafe { (*Self::PTR).iabr[i].read() })
+    }
+
+    /// Writes the raw value of the priority register of `interrupt`
+    ///
+    /// On ARMv6-M this is a read-modify-write of the word shared with three other interrupts.
+    #[inline]
+    unsafe fn write_priority<I>(interrupt: I, prio: u8)
     where
         I: InterruptNumber,
     {
         #[cfg(not(armv6m))]
         {
-            let nr = interrupt.number();
-            self.ipr[usize::from(nr)].write(prio)
+            let nr = Self::interrupt_number(interrupt);
+            (*Self::PTR).ipr[nr].write(prio)
         }
 
         #[cfg(armv6m)]
         {
-            self.ipr[Self::ipr_index(interrupt)].modify(|value| {
+            (*Self::PTR).ipr[Self::ipr_index(interrupt)].modify(|value| {
                 let mask = 0x0000_00ff << Self::ipr_shift(interrupt);
                 let prio = u32::from(prio) << Self::ipr_shift(interrupt);
 
@@ -241,16 +402,41 @@ impl NVIC {
         }
     }
 
-    /// Clears `interrupt`'s pending state
+    /// Returns the raw value of the priority register of `interrupt`
     #[inline]
-    pub fn unpend<I>(interrupt: I)
+    fn hw_priority<I>(interrupt: I) -> u8
     where
         I: InterruptNumber,
     {
-        let nr = interrupt.number();
+        #[cfg(not(armv6m))]
+        {
+            let nr = Self::interrupt_number(interrupt);
+            // NOTE(unsafe) atomic read with no side effects
+            unsafe { (*Self::PTR).ipr[nr].read() }
+        }
 
-        // NOTE(unsafe) atomic stateless write; ICPR doesn't store any state
-        unsafe { (*Self::PTR).icpr[usize::from(nr / 32)].write(1 << (nr % 32)) }
+        #[cfg(armv6m)]
+        {
+            // NOTE(unsafe) atomic read with no side effects
+            let ipr_n = unsafe { (*Self::PTR).ipr[Self::ipr_index(interrupt)].read() };
+            ((ipr_n >> Self::ipr_shift(interrupt)) & 0x0000_00ff) as u8
+        }
+    }
+
+    /// Returns the number of `interrupt`, checking at compile time that all the interrupts of `I`
+    /// fit in the NVIC of the target architecture.
+    #[inline(always)]
+    fn interrupt_number<I>(interrupt: I) -> usize
+    where
+        I: InterruptNumber,
+    {
+        const {
+            assert!(
+                I::MAX_INTERRUPT_NUMBER < NVIC_MAX_INTERRUPTS,
+                "MAX_INTERRUPT_NUMBER exceeds the number of interrupts supported by the NVIC"
+            )
+        };
+        interrupt.number()
     }
 
     #[cfg(armv6m)]
@@ -259,7 +445,7 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        usize::from(interrupt.number()) / 4
+        Self::interrupt_number(interrupt) / 4
     }
 
     #[cfg(armv6m)]
@@ -268,6 +454,490 @@ impl NVIC {
     where
         I: InterruptNumber,
     {
-        (usize::from(interrupt.number()) % 4) * 8
+        (Self::interrupt_number(interrupt) % 4) * 8
+    }
+}
+
+/// A set of interrupts of the device, stored as one bit per interrupt
+///
+/// The bits are laid out like the NVIC registers, so that [`NVIC::mask_set`],
+/// [`NVIC::unmask_set`] and friends only need one access per register.
+///
+/// # Example
+///
+/// ```no_run
+/// # use cortex_m::peripheral::NVIC;
+/// # use cortex_m::peripheral::nvic::InterruptSet;
+/// # use cortex_m::interrupt::InterruptNumber;
+/// # #[derive(Clone, Copy)]
+/// # enum Interrupt { Uart0 = 0, Timer1 = 33 }
+/// # unsafe impl InterruptNumber for Interrupt {
+/// #     const MAX_INTERRUPT_NUMBER: usize = 33;
+/// #     fn number(self) -> usize { self as usize }
+/// #     fn from_number(n: usize) -> cortex_m::result::Result<Self> {
+/// #         match n {
+/// #             0 => Ok(Interrupt::Uart0),
+/// #             33 => Ok(Interrupt::Timer1),
+/// #             _ => Err(cortex_m::result::Error::InvalidVariant(n)),
+/// #         }
+/// #     }
+/// # }
+/// // Save the enable state and only keep the wake-up sources enabled
+/// let saved = NVIC::enabled::<Interrupt>();
+/// let wakeup: InterruptSet<_> = [Interrupt::Uart0, Interrupt::Timer1].into_iter().collect();
+/// unsafe { NVIC::restore_enabled(&wakeup) };
+///
+/// // ... sleep ...
+///
+/// unsafe { NVIC::restore_enabled(&saved) };
+/// ```
+pub struct InterruptSet<I> {
+    words: [u32; NVIC_WORDS],
+    _interrupt: PhantomData<fn() -> I>,
+}
+
+impl<I> InterruptSet<I>
+where
+    I: InterruptNumber,
+{
+    /// Returns an empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        InterruptSet {
+            words: [0; NVIC_WORDS],
+            _interrupt: PhantomData,
+        }
+    }
+
+    /// Adds `interrupt` to the set.
+    #[inline]
+    pub fn insert(&mut self, interrupt: I) {
+        let nr = NVIC::interrupt_number(interrupt);
+        self.words[nr / 32] |= 1 << (nr % 32);
+    }
+
+    /// Removes `interrupt` from the set.
+    #[inline]
+    pub fn remove(&mut self, interrupt: I) {
+        let nr = NVIC::interrupt_number(interrupt);
+        self.words[nr / 32] &= !(1 << (nr % 32));
+    }
+
+    /// Returns `true` if `interrupt` is in the set.
+    #[inline]
+    pub fn contains(&self, interrupt: I) -> bool {
+        let nr = NVIC::interrupt_number(interrupt);
+        self.words[nr / 32] & (1 << (nr % 32)) != 0
+    }
+
+    /// Returns `true` if the set contains no interrupt.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(|&word| word == 0)
+    }
+
+    /// Returns the number of interrupts in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.words()
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the set of interrupts in `self` or in `other`.
+    #[inline]
+    pub fn union(mut self, other: &Self) -> Self {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+        self
+    }
+
+    /// Returns the set of interrupts in both `self` and `other`.
+    #[inline]
+    pub fn intersection(mut self, other: &Self) -> Self {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= other;
+        }
+        self
+    }
+
+    /// Returns the set of interrupts in `self` but not in `other`.
+    #[inline]
+    pub fn difference(mut self, other: &Self) -> Self {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= !other;
+        }
+        self
+    }
+
+    /// Returns an iterator over the interrupts in the set, in increasing order.
+    ///
+    /// Numbers that do not map to a variant of `I` are skipped.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = I> + '_ {
+        self.numbers().filter_map(|nr| I::from_number(nr).ok())
+    }
+
+    /// Returns an iterator over the numbers of the interrupts in the set.
+    #[inline]
+    fn numbers(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words().iter().enumerate().flat_map(|(i, &word)| {
+            (0..32)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| i * 32 + bit)
+        })
+    }
+
+    /// Returns the raw bits of the set, one word per NVIC register.
+    ///
+    /// Only the words covering interrupts up to `I::MAX_INTERRUPT_NUMBER` are returned.
+    #[inline]
+    pub fn words(&self) -> &[u32] {
+        &self.words[..Self::word_count()]
+    }
+
+    /// Returns the number of words needed to hold all the interrupts of `I`.
+    #[inline(always)]
+    fn word_count() -> usize {
+        const {
+            assert!(
+                I::MAX_INTERRUPT_NUMBER < NVIC_MAX_INTERRUPTS,
+                "MAX_INTERRUPT_NUMBER exceeds the number of interrupts supported by the NVIC"
+            )
+        };
+        I::MAX_INTERRUPT_NUMBER / 32 + 1
+    }
+
+    /// Returns the bits of word `i` that correspond to interrupts up to `I::MAX_INTERRUPT_NUMBER`.
+    #[inline(always)]
+    fn word_mask(i: usize) -> u32 {
+        let last = I::MAX_INTERRUPT_NUMBER / 32;
+        if i < last {
+            !0
+        } else {
+            !0 >> (31 - I::MAX_INTERRUPT_NUMBER % 32)
+        }
+    }
+
+    /// Builds a set from the register words returned by `read`, ignoring the bits above
+    /// `I::MAX_INTERRUPT_NUMBER`.
+    #[inline]
+    fn from_registers(mut read: impl FnMut(usize) -> u32) -> Self {
+        let mut set = Self::new();
+        for i in 0..Self::word_count() {
+            set.words[i] = read(i) & Self::word_mask(i);
+        }
+        set
+    }
+}
+
+impl<I> Clone for InterruptSet<I> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for InterruptSet<I> {}
+
+impl<I> PartialEq for InterruptSet<I> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl<I> Eq for InterruptSet<I> {}
+
+impl<I> Default for InterruptSet<I>
+where
+    I: InterruptNumber,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> fmt::Debug for InterruptSet<I>
+where
+    I: InterruptNumber,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.numbers()).finish()
+    }
+}
+
+impl<I> FromIterator<I> for InterruptSet<I>
+where
+    I: InterruptNumber,
+{
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<I> Extend<I> for InterruptSet<I>
+where
+    I: InterruptNumber,
+{
+    #[inline]
+    fn extend<T: IntoIterator<Item = I>>(&mut self, iter: T) {
+        for interrupt in iter {
+            self.insert(interrupt);
+        }
+    }
+}
+
+impl NVIC {
+    /// Splits the NVIC into per-interrupt [`InterruptHandle`]s
+    ///
+    /// The NVIC is consumed, so the handles are the only way left to enable the interrupts of
+    /// `I`.
+    #[inline]
+    pub fn into_handles<I>(self) -> InterruptHandles<I>
+    where
+        I: InterruptNumber,
+    {
+        InterruptHandles {
+            taken: InterruptSet::new(),
+        }
     }
 }
+
+/// The interrupt lines of the device, handed out one by one as [`InterruptHandle`]s
+///
+/// This is obtained from [`NVIC::into_handles`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use cortex_m::peripheral::Peripherals;
+/// # use cortex_m::peripheral::nvic::InterruptHandle;
+/// # use cortex_m::interrupt::InterruptNumber;
+/// # #[derive(Clone, Copy, Debug)]
+/// # enum Interrupt { Uart0 = 0 }
+/// # unsafe impl InterruptNumber for Interrupt {
+/// #     const MAX_INTERRUPT_NUMBER: usize = 0;
+/// #     fn number(self) -> usize { self as usize }
+/// #     fn from_number(n: usize) -> cortex_m::result::Result<Self> {
+/// #         Err(cortex_m::result::Error::InvalidVariant(n))
+/// #     }
+/// # }
+/// struct Uart {
+///     irq: InterruptHandle<Interrupt>,
+/// }
+///
+/// impl Uart {
+///     fn start(&mut self) {
+///         self.irq.unpend();
+///         self.irq.enable();
+///     }
+/// }
+///
+/// let p = Peripherals::take().unwrap();
+/// let mut handles = p.NVIC.into_handles::<Interrupt>();
+/// let mut uart = Uart {
+///     irq: handles.take(Interrupt::Uart0).unwrap(),
+/// };
+/// uart.start();
+/// ```
+#[derive(Debug)]
+pub struct InterruptHandles<I>
+where
+    I: InterruptNumber,
+{
+    taken: InterruptSet<I>,
+}
+
+impl<I> InterruptHandles<I>
+where
+    I: InterruptNumber,
+{
+    /// Takes the handle of `interrupt`
+    ///
+    /// Returns [`Error::AlreadyTaken`] if the handle was already taken and not released.
+    #[inline]
+    pub fn take(&mut self, interrupt: I) -> Result<InterruptHandle<I>> {
+        if self.taken.contains(interrupt) {
+            return Err(Error::AlreadyTaken);
+        }
+        self.taken.insert(interrupt);
+        Ok(InterruptHandle { interrupt })
+    }
+
+    /// Gives back the handle of an interrupt, so that it can be taken again
+    #[inline]
+    pub fn release(&mut self, handle: InterruptHandle<I>) {
+        self.taken.remove(handle.interrupt);
+    }
+
+    /// Returns the interrupts whose handle is currently taken
+    #[inline]
+    pub fn taken(&self) -> &InterruptSet<I> {
+        &self.taken
+    }
+}
+
+/// Ownership of a single interrupt line
+///
+/// Only the owner of the handle enables, disables, pends and prioritizes the interrupt, so
+/// enabling it cannot break a mask-based critical section held elsewhere, and does not need to be
+/// `unsafe` like [`NVIC::unmask`].
+///
+/// Priority-based critical sections (BASEPRI, see [`interrupt::free_below`] and the
+/// `critical-section-basepri` feature) rely on the priority of the interrupts instead, which is
+/// why [`InterruptHandle::set_priority`] stays `unsafe`. Their users must give the interrupt a
+/// priority at or below the ceiling before enabling it, as the reset priority is the most urgent
+/// one.
+///
+/// [`interrupt::free_below`]: crate::interrupt
+#[derive(Debug)]
+pub struct InterruptHandle<I> {
+    interrupt: I,
+}
+
+impl<I> InterruptHandle<I>
+where
+    I: InterruptNumber,
+{
+    /// Returns the interrupt this handle owns
+    #[inline]
+    pub fn interrupt(&self) -> I {
+        self.interrupt
+    }
+
+    /// Enables the interrupt
+    ///
+    /// With `set_basepri_critical_section!`, the priority of the interrupt must be at or below the
+    /// ceiling if its handler uses `critical-section`; this is part of the safety contract of the
+    /// macro.
+    #[inline]
+    pub fn enable(&mut self) {
+        // NOTE(unsafe) no one else can mask this interrupt, so no mask-based critical section
+        // can be broken
+        unsafe { NVIC::unmask(self.interrupt) }
+    }
+
+    /// Disables the interrupt
+    #[inline]
+    pub fn disable(&mut self) {
+        NVIC::mask(self.interrupt)
+    }
+
+    /// Checks if the interrupt is enabled
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        NVIC::is_enabled(self.interrupt)
+    }
+
+    /// Forces the interrupt into pending state
+    #[inline]
+    pub fn pend(&mut self) {
+        NVIC::pend(self.interrupt)
+    }
+
+    /// Clears the pending state of the interrupt
+    #[inline]
+    pub fn unpend(&mut self) {
+        NVIC::unpend(self.interrupt)
+    }
+
+    /// Checks if the interrupt is pending
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        NVIC::is_pending(self.interrupt)
+    }
+
+    /// Checks if the interrupt is being serviced by a handler
+    ///
+    /// This method is not available on ARMv6-M chips.
+    #[cfg(not(armv6m))]
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        NVIC::is_active(self.interrupt)
+    }
+
+    /// Returns the priority of the interrupt
+    ///
+    /// See [`NVIC::get_priority`].
+    #[inline]
+    pub fn get_priority<P>(&self) -> Result<P>
+    where
+        P: PriorityNumber,
+    {
+        NVIC::get_priority(self.interrupt)
+    }
+
+    /// Returns the priority of the interrupt as a [`LogicalPriority`], for a device implementing
+    /// `bits` priority bits
+    #[inline]
+    pub fn get_logical_priority(&self, bits: u8) -> Result<LogicalPriority> {
+        NVIC::get_logical_priority(self.interrupt, bits)
+    }
+
+    /// Sets the priority of the interrupt
+    ///
+    /// See [`NVIC::set_priority`]. On ARMv6-M, the read-modify-write of the priority register is
+    /// done in a critical section, as the register is shared with the handles of three other
+    /// interrupts.
+    ///
+    /// # Unsafety
+    ///
+    /// Changing priority levels can break priority-based critical sections and compromise memory
+    /// safety.
+    #[inline]
+    pub unsafe fn set_priority<P>(&mut self, prio: P)
+    where
+        P: HwPriority,
+    {
+        let prio = prio.to_hw();
+
+        #[cfg(not(armv6m))]
+        NVIC::write_priority(self.interrupt, prio);
+
+        #[cfg(armv6m)]
+        crate::interrupt::free(|_| NVIC::write_priority(self.interrupt, prio));
+    }
+}
+
+/// Returns the number of priority bits needed to represent all the levels of `P`, checking at
+/// compile time that they fit in a priority byte.
+#[inline(always)]
+fn priority_bits<P: PriorityNumber>() -> u32 {
+    const {
+        assert!(
+            P::MAX_PRIORITY_NUMBER <= 0xFF,
+            "MAX_PRIORITY_NUMBER does not fit in the 8-bit priority fields"
+        )
+    };
+    usize::BITS - P::MAX_PRIORITY_NUMBER.leading_zeros()
+}
+
+/// Converts a logical priority level into its hardware encoding.
+///
+/// The level is inverted, so that `P::MAX_PRIORITY_NUMBER` becomes the most urgent hardware
+/// priority (`0`), and shifted into the most significant bits of the byte.
+#[inline]
+pub(crate) fn priority_to_hw<P: PriorityNumber>(prio: P) -> u8 {
+    let level = P::MAX_PRIORITY_NUMBER - prio.number();
+    (level << (8 - priority_bits::<P>())) as u8
+}
+
+/// Converts a hardware priority encoding back into a logical priority level.
+///
+/// This is the inverse of [`priority_to_hw`]. The unimplemented low bits of `prio` are ignored.
+#[inline]
+pub(crate) fn priority_from_hw<P: PriorityNumber>(prio: u8) -> Result<P> {
+    let level = usize::from(prio) >> (8 - priority_bits::<P>());
+    let number = P::MAX_PRIORITY_NUMBER
+        .checked_sub(level)
+        .ok_or(Error::InvalidVariant(usize::from(prio)))?;
+    P::from_number(number)
+}
@@ -10,6 +10,9 @@
 use crate::interrupt;
 use crate::peripheral::SAU;
 use bitfield::bitfield;
+use cortex_m_types::result::{Error, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use volatile_register::{RO, RW};
 
 /// Register block
@@ -35,6 +38,8 @@ bitfield! {
     /// Control Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Ctrl(u32);
     get_enable, set_enable: 0;
     get_allns, set_allns: 1;
@@ -44,6 +49,8 @@ bitfield! {
     /// Type Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Type(u32);
     u8;
     sregion, _: 7, 0;
@@ -53,6 +60,8 @@ bitfield! {
     /// Region Number Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Rnr(u32);
     u8;
     get_region, set_region: 7, 0;
@@ -62,6 +71,8 @@ bitfield! {
     /// Region Base Address Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Rbar(u32);
     u32;
     get_baddr, set_baddr: 31, 5;
@@ -71,6 +82,8 @@ bitfield! {
     /// Region Limit Address Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Rlar(u32);
     u32;
     get_laddr, set_laddr: 31, 5;
@@ -82,6 +95,8 @@ bitfield! {
     /// Secure Fault Status Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Sfsr(u32);
     invep, _: 0;
     invis, _: 1;
@@ -97,6 +112,8 @@ bitfield! {
     /// Secure Fault Address Register description
     #[repr(C)]
     #[derive(Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Sfar(u32);
     u32;
     address, _: 31, 0;
@@ -104,6 +121,8 @@ bitfield! {
 
 /// Possible attribute of a SAU region.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SauRegionAttribute {
     /// SAU region is Secure
     Secure,
@@ -115,6 +134,8 @@ pub enum SauRegionAttribute {
 
 /// Description of a SAU region.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SauRegion {
     /// First address of the region, its 5 least significant bits must be set to zero.
     pub base_address: u32,
@@ -126,6 +147,8 @@ pub struct SauRegion {
 
 /// Possible error values returned by the SAU methods.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SauError {
     /// The region number parameter to set or get a region must be between 0 and
     /// region_numbers() - 1.
@@ -136,6 +159,23 @@ pub enum SauError {
     WrongLimitAddress,
 }
 
+impl SauError {
+    /// Converts an error of `SAU::try_set_region` or `SAU::try_get_region`, which only return
+    /// these three errors.
+    #[inline]
+    fn from_region_error(error: Error) -> Self {
+        match error {
+            Error::IndexOutOfBounds { .. } => SauError::RegionNumberTooBig,
+            Error::InvalidFieldValue { field: "BADDR", .. } => SauError::WrongBaseAddress,
+            Error::InvalidFieldValue { field: "LADDR", .. } => SauError::WrongLimitAddress,
+            error => unreachable!("unexpected SAU region error: {:?}", error),
+        }
+    }
+}
+
+/// Bits of the SAU region addresses below the 32-byte region granularity
+const SAU_ADDRESS_GRANULE_MASK: u32 = 0x1F;
+
 impl SAU {
     /// Get the number of implemented SAU regions.
     #[inline]
@@ -161,52 +201,79 @@ impl SAU {
     /// The region number must be valid.
     /// This function is executed under a critical section to prevent having inconsistent results.
     #[inline]
-    pub fn set_region(&mut self, region_number: u8, region: SauRegion) -> Result<(), SauError> {
+    pub fn set_region(
+        &mut self,
+        region_number: u8,
+        region: SauRegion,
+    ) -> core::result::Result<(), SauError> {
+        self.try_set_region(region_number, region)
+            .map_err(SauError::from_region_error)
+    }
+
+    /// Set a SAU region to a region number.
+    ///
+    /// Same as [`SAU::set_region`], but returns a detailed [`Error`]:
+    /// * [`Error::IndexOutOfBounds`] if the region number is not implemented.
+    /// * [`Error::InvalidFieldValue`] for the `BADDR` field if the base address is not 32 bytes
+    ///   aligned, with the bitmask of the bits that may be set.
+    /// * [`Error::InvalidFieldValue`] for the `LADDR` field if the 5 least significant bits of the
+    ///   limit address are not set, with the bitmask of the bits that must be set.
+    #[inline]
+    pub fn try_set_region(&mut self, region_number: u8, region: SauRegion) -> Result<()> {
+        let base_address = region.base_address;
+        let limit_address = region.limit_address;
+        let attribute = region.attribute;
+
+        if base_address & SAU_ADDRESS_GRANULE_MASK != 0 {
+            return Err(Error::InvalidFieldValue {
+                field: "BADDR",
+                value: base_address as usize,
+                bitmask: !SAU_ADDRESS_GRANULE_MASK as usize,
+            });
+        }
+        if limit_address & SAU_ADDRESS_GRANULE_MASK != SAU_ADDRESS_GRANULE_MASK {
+            return Err(Error::InvalidFieldValue {
+                field: "LADDR",
+                value: limit_address as usize,
+                bitmask: SAU_ADDRESS_GRANULE_MASK as usize,
+            });
+        }
+
         interrupt::free(|_| {
-            let base_address = region.base_address;
-            let limit_address = region.limit_address;
-            let attribute = region.attribute;
-
-            if region_number >= self.region_numbers() {
-                Err(SauError::RegionNumberTooBig)
-            } else if base_address & 0x1F != 0 {
-                Err(SauError::WrongBaseAddress)
-            } else if limit_address & 0x1F != 0x1F {
-                Err(SauError::WrongLimitAddress)
-            } else {
-                // All fields of these registers are going to be modified so we don't need to read them
-                // before.
-                let mut rnr = Rnr(0);
-                let mut rbar = Rbar(0);
-                let mut rlar = Rlar(0);
-
-                rnr.set_region(region_number);
-                rbar.set_baddr(base_address >> 5);
-                rlar.set_laddr(limit_address >> 5);
-
-                match attribute {
-                    SauRegionAttribute::Secure => {
-                        rlar.set_nsc(false);
-                        rlar.set_enable(false);
-                    }
-                    SauRegionAttribute::NonSecureCallable => {
-                        rlar.set_nsc(true);
-                        rlar.set_enable(true);
-                    }
-                    SauRegionAttribute::NonSecure => {
-                        rlar.set_nsc(false);
-                        rlar.set_enable(true);
-                    }
-                }
+            self.check_region_number(region_number)?;
+
+            // All fields of these registers are going to be modified so we don't need to read them
+            // before.
+            let mut rnr = Rnr(0);
+            let mut rbar = Rbar(0);
+            let mut rlar = Rlar(0);
+
+            rnr.set_region(region_number);
+            rbar.set_baddr(base_address >> 5);
+            rlar.set_laddr(limit_address >> 5);
 
-                unsafe {
-                    self.rnr.write(rnr);
-                    self.rbar.write(rbar);
-                    self.rlar.write(rlar);
+            match attribute {
+                SauRegionAttribute::Secure => {
+                    rlar.set_nsc(false);
+                    rlar.set_enable(false);
                 }
+                SauRegionAttribute::NonSecureCallable => {
+                    rlar.set_nsc(true);
+                    rlar.set_enable(true);
+                }
+                SauRegionAttribute::NonSecure => {
+                    rlar.set_nsc(false);
+                    rlar.set_enable(true);
+                }
+            }
 
-                Ok(())
+            unsafe {
+                self.rnr.write(rnr);
+                self.rbar.write(rbar);
+                self.rlar.write(rlar);
             }
+
+            Ok(())
         })
     }
 
@@ -214,30 +281,58 @@ impl SAU {
     /// The region number must be valid.
     /// This function is executed under a critical section to prevent having inconsistent results.
     #[inline]
-    pub fn get_region(&mut self, region_number: u8) -> Result<SauRegion, SauError> {
+    pub fn get_region(&mut self, region_number: u8) -> core::result::Result<SauRegion, SauError> {
+        self.try_get_region(region_number)
+            .map_err(SauError::from_region_error)
+    }
+
+    /// Get a region from the SAU.
+    ///
+    /// Same as [`SAU::get_region`], but returns [`Error::IndexOutOfBounds`] if the region number
+    /// is not implemented.
+    #[inline]
+    pub fn try_get_region(&mut self, region_number: u8) -> Result<SauRegion> {
         interrupt::free(|_| {
-            if region_number >= self.region_numbers() {
-                Err(SauError::RegionNumberTooBig)
-            } else {
-                unsafe {
-                    self.rnr.write(Rnr(region_number.into()));
-                }
+            self.check_region_number(region_number)?;
 
-                let rbar = self.rbar.read();
-                let rlar = self.rlar.read();
+            unsafe {
+                self.rnr.write(Rnr(region_number.into()));
+            }
 
-                let attribute = match (rlar.get_enable(), rlar.get_nsc()) {
-                    (false, _) => SauRegionAttribute::Secure,
-                    (true, false) => SauRegionAttribute::NonSecure,
-                    (true, true) => SauRegionAttribute::NonSecureCallable,
-                };
+            let rbar = self.rbar.read();
+            let rlar = self.rlar.read();
 
-                Ok(SauRegion {
-                    base_address: rbar.get_baddr() << 5,
-                    limit_address: (rlar.get_laddr() << 5) | 0x1F,
-                    attribute,
-                })
-            }
+            let attribute = match (rlar.get_enable(), rlar.get_nsc()) {
+                (false, _) => SauRegionAttribute::Secure,
+                (true, false) => SauRegionAttribute::NonSecure,
+                (true, true) => SauRegionAttribute::NonSecureCallable,
+            };
+
+            Ok(SauRegion {
+                base_address: rbar.get_baddr() << 5,
+                limit_address: (rlar.get_laddr() << 5) | 0x1F,
+                attribute,
+            })
+        })
+    }
+
+    /// Checks that `region_number` is an implemented SAU region.
+    #[inline]
+    fn check_region_number(&self, region_number: u8) -> Result<()> {
+        check_region_number(region_number, self.region_numbers())
+    }
+}
+
+/// Checks that `region_number` is one of the `region_numbers` implemented SAU regions.
+#[inline]
+pub(crate) fn check_region_number(region_number: u8, region_numbers: u8) -> Result<()> {
+    if region_number >= region_numbers {
+        Err(Error::IndexOutOfBounds {
+            index: usize::from(region_number),
+            min: 0,
+            max: usize::from(region_numbers).saturating_sub(1),
         })
+    } else {
+        Ok(())
     }
 }
@@ -2,15 +2,23 @@
 
 use core::ptr;
 
+#[cfg(not(any(armv6m, armv8m_base)))]
+use bitfield::bitfield;
 use volatile_register::RW;
 
 #[cfg(not(armv6m))]
 use super::cpuid::CsselrCacheType;
+use super::nvic::priority_from_hw;
 #[cfg(not(armv6m))]
 use super::CBP;
 #[cfg(not(armv6m))]
 use super::CPUID;
 use super::SCB;
+#[cfg(not(any(armv6m, armv8m_base)))]
+use crate::priority::PriorityGrouping;
+use crate::priority::{HwPriority, LogicalPriority};
+use crate::{ExceptionNumber, PriorityNumber};
+use cortex_m_types::result::{Error, Result};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -100,6 +108,8 @@ pub struct RegisterBlock {
 /// FPU access mode
 #[cfg(has_fpu)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FpuAccessMode {
     /// FPU is not accessible
     Disabled,
@@ -168,54 +178,63 @@ impl SCB {
 
 impl SCB {
     /// Returns the active exception number
+    ///
+    /// Core exceptions are converted to `E`, which is usually [`Exception`] or the device specific
+    /// exception enum of a PAC. An error is returned if the active exception number does not match
+    /// any variant of `E`.
     #[inline]
-    pub fn vect_active() -> VectActive {
+    pub fn vect_active<E: ExceptionNumber>() -> Result<VectActive<E>> {
         let icsr = unsafe { ptr::read(&(*SCB::PTR).icsr as *const _ as *const u32) };
 
-        // NOTE(unsafe): Assume correctly selected target.
-        unsafe { VectActive::from(icsr as u8).unwrap_unchecked() }
+        VectActive::from((icsr & SCB_ICSR_VECTACTIVE_MASK) as u16)
     }
 }
 
+const SCB_ICSR_VECTACTIVE_MASK: u32 = 0x1FF;
+
 /// Processor core exceptions (internal interrupts)
+///
+/// Each variant is represented by its exception number.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[cfg_attr(feature = "std", derive(PartialOrd, Hash))]
+#[repr(u8)]
 pub enum Exception {
     /// Non maskable interrupt
-    NonMaskableInt,
+    NonMaskableInt = 2,
 
     /// Hard fault interrupt
-    HardFault,
+    HardFault = 3,
 
     /// Memory management interrupt (not present on Cortex-M0 variants)
     #[cfg(not(armv6m))]
-    MemoryManagement,
+    MemoryManagement = 4,
 
     /// Bus fault interrupt (not present on Cortex-M0 variants)
     #[cfg(not(armv6m))]
-    BusFault,
+    BusFault = 5,
 
     /// Usage fault interrupt (not present on Cortex-M0 variants)
     #[cfg(not(armv6m))]
-    UsageFault,
+    UsageFault = 6,
 
     /// Secure fault interrupt (only on ARMv8-M)
     #[cfg(any(armv8m, native))]
-    SecureFault,
+    SecureFault = 7,
 
     /// SV call interrupt
-    SVCall,
+    SVCall = 11,
 
     /// Debug monitor interrupt (not present on Cortex-M0 variants)
     #[cfg(not(armv6m))]
-    DebugMonitor,
+    DebugMonitor = 12,
 
     /// Pend SV interrupt
-    PendSV,
+    PendSV = 14,
 
     /// System Tick interrupt
-    SysTick,
+    SysTick = 15,
 }
 
 impl Exception {
@@ -224,67 +243,78 @@ impl Exception {
     /// The return value is always within the closed range `[-1, -14]`
     #[inline]
     pub fn irqn(self) -> i8 {
-        match self {
-            Exception::NonMaskableInt => -14,
-            Exception::HardFault => -13,
+        self as i8 - 16
+    }
+}
+
+unsafe impl ExceptionNumber for Exception {
+    const MAX_EXCEPTION_NUMBER: usize = Self::SysTick as usize;
+
+    #[inline]
+    fn number(self) -> usize {
+        self as _
+    }
+
+    #[inline]
+    fn from_number(number: usize) -> Result<Self> {
+        match number {
+            2 => Ok(Exception::NonMaskableInt),
+            3 => Ok(Exception::HardFault),
             #[cfg(not(armv6m))]
-            Exception::MemoryManagement => -12,
+            4 => Ok(Exception::MemoryManagement),
             #[cfg(not(armv6m))]
-            Exception::BusFault => -11,
+            5 => Ok(Exception::BusFault),
             #[cfg(not(armv6m))]
-            Exception::UsageFault => -10,
+            6 => Ok(Exception::UsageFault),
             #[cfg(any(armv8m, native))]
-            Exception::SecureFault => -9,
-            Exception::SVCall => -5,
+            7 => Ok(Exception::SecureFault),
+            11 => Ok(Exception::SVCall),
             #[cfg(not(armv6m))]
-            Exception::DebugMonitor => -4,
-            Exception::PendSV => -2,
-            Exception::SysTick => -1,
+            12 => Ok(Exception::DebugMonitor),
+            14 => Ok(Exception::PendSV),
+            15 => Ok(Exception::SysTick),
+            _ => Err(Error::InvalidVariant(number)),
         }
     }
 }
 
+/// System handlers, exceptions with configurable priority
+#[deprecated(since = "0.8.0", note = "Use `Exception` instead")]
+pub type SystemHandler = Exception;
+
 /// Active exception number
+///
+/// `E` is the enum of core exceptions, usually [`Exception`] or the device specific exception enum
+/// of a PAC.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[cfg_attr(feature = "std", derive(PartialOrd, Hash))]
-pub enum VectActive {
+pub enum VectActive<E = Exception> {
     /// Thread mode
     ThreadMode,
 
     /// Processor core exception (internal interrupts)
-    Exception(Exception),
+    Exception(E),
 
     /// Device specific exception (external interrupts)
     Interrupt {
-        /// Interrupt number. This number is always within half open range `[0, 240)`
-        irqn: u8,
+        /// Interrupt number. This number is always within half open range `[0, 496)`
+        irqn: u16,
     },
 }
 
-impl VectActive {
-    /// Converts a `byte` into `VectActive`
+impl<E: ExceptionNumber> VectActive<E> {
+    /// Converts an exception number into `VectActive`
+    ///
+    /// An error is returned if `vect_active` is the number of a core exception that does not
+    /// match any variant of `E`.
     #[inline]
-    pub fn from(vect_active: u8) -> Option<Self> {
-        Some(match vect_active {
+    pub fn from(vect_active: u16) -> Result<Self> {
+        Ok(match vect_active {
             0 => VectActive::ThreadMode,
-            2 => VectActive::Exception(Exception::NonMaskableInt),
-            3 => VectActive::Exception(Exception::HardFault),
-            #[cfg(not(armv6m))]
-            4 => VectActive::Exception(Exception::MemoryManagement),
-            #[cfg(not(armv6m))]
-            5 => VectActive::Exception(Exception::BusFault),
-            #[cfg(not(armv6m))]
-            6 => VectActive::Exception(Exception::UsageFault),
-            #[cfg(any(armv8m, native))]
-            7 => VectActive::Exception(Exception::SecureFault),
-            11 => VectActive::Exception(Exception::SVCall),
-            #[cfg(not(armv6m))]
-            12 => VectActive::Exception(Exception::DebugMonitor),
-            14 => VectActive::Exception(Exception::PendSV),
-            15 => VectActive::Exception(Exception::SysTick),
             irqn if irqn >= 16 => VectActive::Interrupt { irqn: irqn - 16 },
-            _ => return None,
+            number => VectActive::Exception(E::from_number(usize::from(number))?),
         })
     }
 }
@@ -834,9 +864,11 @@ impl SCB {
     }
 }
 
-const SCB_AIRCR_VECTKEY: u32 = 0x05FA << 16;
-const SCB_AIRCR_PRIGROUP_MASK: u32 = 0x7 << 8;
+pub(crate) const SCB_AIRCR_VECTKEY: u32 = 0x05FA << 16;
+pub(crate) const SCB_AIRCR_PRIGROUP_MASK: u32 = 0x7 << 8;
 const SCB_AIRCR_SYSRESETREQ: u32 = 1 << 2;
+#[cfg(all(not(armv6m), not(armv8m)))]
+const SCB_AIRCR_VECTRESET: u32 = 1 << 0;
 
 impl SCB {
     /// Initiate a system reset request to reset the MCU
@@ -858,6 +890,80 @@ impl SCB {
             crate::asm::nop(); // avoid rust-lang/rust#28728
         }
     }
+
+    /// Initiate a local reset of the core, through `AIRCR.VECTRESET`
+    ///
+    /// Unlike [`SCB::sys_reset`], only the core is reset: the peripherals of the MCU and the
+    /// debug logic keep their state.
+    ///
+    /// This method is only available on ARMv7-M; ARMv6-M and ARMv8-M do not implement
+    /// `VECTRESET`.
+    ///
+    /// # Safety
+    ///
+    /// The architecture only defines the effect of `VECTRESET` while the core is halted in Debug
+    /// state. Cortex-M3 and Cortex-M4 perform a local reset in any state, but other
+    /// implementations may not, and the peripherals are not reset, so they may keep raising
+    /// interrupts or DMA requests the program does not expect after the reset.
+    #[cfg(all(not(armv6m), not(armv8m)))]
+    #[inline]
+    pub unsafe fn vect_reset() -> ! {
+        crate::asm::dsb();
+        (*Self::PTR).aircr.modify(|r| {
+            SCB_AIRCR_VECTKEY | // otherwise the write is ignored
+            r & SCB_AIRCR_PRIGROUP_MASK | // keep priority group unchanged
+            SCB_AIRCR_VECTRESET
+        });
+        crate::asm::dsb();
+        loop {
+            // wait for the reset
+            crate::asm::nop(); // avoid rust-lang/rust#28728
+        }
+    }
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+const SCB_AIRCR_PRIGROUP_SHIFT: u32 = 8;
+/// AIRCR bits that must be written back unchanged: ENDIANNESS and, on ARMv8-M, SYSRESETREQS,
+/// BFHFNMINS and PRIS
+#[cfg(not(any(armv6m, armv8m_base)))]
+const SCB_AIRCR_PRESERVE_MASK: u32 = (1 << 15) | (1 << 14) | (1 << 13) | (1 << 3);
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+impl SCB {
+    /// Returns the priority grouping (`AIRCR.PRIGROUP`)
+    ///
+    /// This method is not available on ARMv6-M and ARMv8-M Baseline, which do not implement
+    /// priority grouping.
+    #[inline]
+    pub fn priority_grouping() -> PriorityGrouping {
+        // NOTE(unsafe) atomic read with no side effects
+        let aircr = unsafe { (*Self::PTR).aircr.read() };
+        let prigroup = (aircr & SCB_AIRCR_PRIGROUP_MASK) >> SCB_AIRCR_PRIGROUP_SHIFT;
+        // NOTE(unwrap) the field is 3 bits wide
+        PriorityGrouping::from_prigroup(prigroup as u8).unwrap()
+    }
+
+    /// Sets the priority grouping (`AIRCR.PRIGROUP`)
+    ///
+    /// The write includes the `VECTKEY` value, without which the register ignores writes, and
+    /// leaves the other fields unchanged.
+    ///
+    /// This method is not available on ARMv6-M and ARMv8-M Baseline, which do not implement
+    /// priority grouping.
+    ///
+    /// # Unsafety
+    ///
+    /// Changing the priority grouping changes which exceptions can preempt each other, which can
+    /// break priority-based critical sections and compromise memory safety.
+    #[inline]
+    pub unsafe fn set_priority_grouping(&mut self, grouping: PriorityGrouping) {
+        self.aircr.modify(|r| {
+            SCB_AIRCR_VECTKEY
+                | (r & SCB_AIRCR_PRESERVE_MASK)
+                | (u32::from(grouping.prigroup()) << SCB_AIRCR_PRIGROUP_SHIFT)
+        });
+    }
 }
 
 const SCB_ICSR_PENDSVSET: u32 = 1 << 28;
@@ -912,114 +1018,87 @@ impl SCB {
     }
 }
 
-/// System handlers, exceptions with configurable priority
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[repr(u8)]
-pub enum SystemHandler {
-    // NonMaskableInt, // priority is fixed
-    // HardFault, // priority is fixed
-    /// Memory management interrupt (not present on Cortex-M0 variants)
-    #[cfg(not(armv6m))]
-    MemoryManagement = 4,
-
-    /// Bus fault interrupt (not present on Cortex-M0 variants)
-    #[cfg(not(armv6m))]
-    BusFault = 5,
-
-    /// Usage fault interrupt (not present on Cortex-M0 variants)
-    #[cfg(not(armv6m))]
-    UsageFault = 6,
-
-    /// Secure fault interrupt (only on ARMv8-M)
-    #[cfg(any(armv8m, native))]
-    SecureFault = 7,
-
-    /// SV call interrupt
-    SVCall = 11,
-
-    /// Debug monitor interrupt (not present on Cortex-M0 variants)
-    #[cfg(not(armv6m))]
-    DebugMonitor = 12,
-
-    /// Pend SV interrupt
-    PendSV = 14,
-
-    /// System Tick interrupt
-    SysTick = 15,
-}
-
 impl SCB {
-    /// Returns the hardware priority of `system_handler`
+    /// Returns the priority of `exception`
+    ///
+    /// The hardware encoding is converted back to the logical priority level `P`. See
+    /// [`NVIC::set_priority`](struct.NVIC.html#method.set_priority) for more details.
     ///
-    /// *NOTE*: Hardware priority does not exactly match logical priority levels. See
-    /// [`NVIC.get_priority`](struct.NVIC.html#method.get_priority) for more details.
+    /// An error is returned if `exception` does not have a configurable priority, or if the value
+    /// currently held in the register does not match any variant of `P`.
     #[inline]
-    pub fn get_priority(system_handler: SystemHandler) -> u8 {
-        let index = system_handler as u8;
-
-        #[cfg(not(armv6m))]
-        {
-            // NOTE(unsafe) atomic read with no side effects
-
-            // NOTE(unsafe): Index is bounded to [4,15] by SystemHandler design.
-            // TODO: Review it after rust-lang/rust/issues/13926 will be fixed.
-            let priority_ref = unsafe { (*Self::PTR).shpr.get_unchecked(usize::from(index - 4)) };
-
-            priority_ref.read()
-        }
-
-        #[cfg(armv6m)]
-        {
-            // NOTE(unsafe) atomic read with no side effects
+    pub fn get_priority<E, P>(exception: E) -> Result<P>
+    where
+        E: ExceptionNumber,
+        P: PriorityNumber,
+    {
+        priority_from_hw(Self::hw_priority(exception)?)
+    }
 
-            // NOTE(unsafe): Index is bounded to [11,15] by SystemHandler design.
-            // TODO: Review it after rust-lang/rust/issues/13926 will be fixed.
-            let priority_ref = unsafe {
-                (*Self::PTR)
-                    .shpr
-                    .get_unchecked(usize::from((index - 8) / 4))
-            };
-
-            let shpr = priority_ref.read();
-            let prio = (shpr >> (8 * (index % 4))) & 0x0000_00ff;
-            prio as u8
-        }
+    /// Returns the priority of `exception` as a [`LogicalPriority`], for a device implementing
+    /// `bits` priority bits
+    ///
+    /// An error is returned if `exception` does not have a configurable priority, or if `bits` is
+    /// not between 1 and 8.
+    #[inline]
+    pub fn get_logical_priority<E>(exception: E, bits: u8) -> Result<LogicalPriority>
+    where
+        E: ExceptionNumber,
+    {
+        LogicalPriority::from_hw(Self::hw_priority(exception)?, bits)
     }
 
-    /// Sets the hardware priority of `system_handler` to `prio`
+    /// Sets the priority of `exception` to `prio`
     ///
-    /// *NOTE*: Hardware priority does not exactly match logical priority levels. See
-    /// [`NVIC.get_priority`](struct.NVIC.html#method.get_priority) for more details.
+    /// `prio` is a logical priority level, or a [`LogicalPriority`]. See
+    /// [`NVIC::set_priority`](struct.NVIC.html#method.set_priority) for more details.
     ///
     /// On ARMv6-M, updating a system handler priority requires a read-modify-write operation. On
     /// ARMv7-M, the operation is performed in a single, atomic write operation.
     ///
+    /// This function is only allowed on exceptions with a configurable priority. Calling it with
+    /// `NonMaskableInt`, `HardFault` or a reserved exception number will do nothing. Use
+    /// [`SCB::try_set_priority`] to detect this case.
+    ///
     /// # Unsafety
     ///
     /// Changing priority levels can break priority-based critical sections (see
     /// [`register::basepri`](crate::register::basepri)) and compromise memory safety.
     #[inline]
-    pub unsafe fn set_priority(&mut self, system_handler: SystemHandler, prio: u8) {
-        let index = system_handler as u8;
+    pub unsafe fn set_priority<E, P>(&mut self, exception: E, prio: P)
+    where
+        E: ExceptionNumber,
+        P: HwPriority,
+    {
+        let _ = self.try_set_priority(exception, prio);
+    }
+
+    /// Sets the priority of `exception` to `prio`
+    ///
+    /// Same as [`SCB::set_priority`], but returns [`Error::InvalidVariant`] with the exception
+    /// number if `exception` does not have a configurable priority.
+    ///
+    /// # Unsafety
+    ///
+    /// Changing priority levels can break priority-based critical sections (see
+    /// [`register::basepri`](crate::register::basepri)) and compromise memory safety.
+    #[inline]
+    pub unsafe fn try_set_priority<E, P>(&mut self, exception: E, prio: P) -> Result<()>
+    where
+        E: ExceptionNumber,
+        P: HwPriority,
+    {
+        let index = Self::shpr_index(exception)?;
+        let prio = prio.to_hw();
 
         #[cfg(not(armv6m))]
         {
-            // NOTE(unsafe): Index is bounded to [4,15] by SystemHandler design.
-            // TODO: Review it after rust-lang/rust/issues/13926 will be fixed.
-            let priority_ref = (*Self::PTR).shpr.get_unchecked(usize::from(index - 4));
-
-            priority_ref.write(prio)
+            self.shpr[index].write(prio)
         }
 
         #[cfg(armv6m)]
         {
-            // NOTE(unsafe): Index is bounded to [11,15] by SystemHandler design.
-            // TODO: Review it after rust-lang/rust/issues/13926 will be fixed.
-            let priority_ref = (*Self::PTR)
-                .shpr
-                .get_unchecked(usize::from((index - 8) / 4));
-
-            priority_ref.modify(|value| {
+            self.shpr[(index - 8) / 4].modify(|value| {
                 let shift = 8 * (index % 4);
                 let mask = 0x0000_00ff << shift;
                 let prio = u32::from(prio) << shift;
@@ -1027,19 +1106,70 @@ impl SCB {
                 (value & !mask) | prio
             });
         }
+
+        Ok(())
+    }
+
+    /// Returns the raw value of the priority field of `exception`
+    #[inline]
+    fn hw_priority<E: ExceptionNumber>(exception: E) -> Result<u8> {
+        let index = Self::shpr_index(exception)?;
+
+        #[cfg(not(armv6m))]
+        {
+            // NOTE(unsafe) atomic read with no side effects
+            Ok(unsafe { (*Self::PTR).shpr[index].read() })
+        }
+
+        #[cfg(armv6m)]
+        {
+            // NOTE(unsafe) atomic read with no side effects
+            let shpr = unsafe { (*Self::PTR).shpr[(index - 8) / 4].read() };
+            Ok(((shpr >> (8 * (index % 4))) & 0x0000_00ff) as u8)
+        }
+    }
+
+    /// Returns the index of the priority field of `exception` in the SHPR registers
+    ///
+    /// On ARMv7-M, the index is the exception number minus 4, as `shpr[0]` points to SHPR1. On
+    /// ARMv6-M, the index is the exception number, as SHPR2 and SHPR3 are only word accessible.
+    #[inline]
+    fn shpr_index<E: ExceptionNumber>(exception: E) -> Result<usize> {
+        match exception.number() {
+            #[cfg(not(armv6m))]
+            number @ 4..=15 => Ok(number - 4),
+            #[cfg(armv6m)]
+            number @ (11 | 14 | 15) => Ok(number),
+            number => Err(Error::InvalidVariant(number)),
+        }
     }
 
     /// Return the bit position of the exception enable bit in the SHCSR register
     #[inline]
     #[cfg(not(any(armv6m, armv8m_base)))]
-    fn shcsr_enable_shift(exception: Exception) -> Option<u32> {
-        match exception {
-            Exception::MemoryManagement => Some(16),
-            Exception::BusFault => Some(17),
-            Exception::UsageFault => Some(18),
+    fn shcsr_enable_shift<E: ExceptionNumber>(exception: E) -> Result<u32> {
+        match exception.number() {
+            4 => Ok(16),
+            5 => Ok(17),
+            6 => Ok(18),
             #[cfg(armv8m_main)]
-            Exception::SecureFault => Some(19),
-            _ => None,
+            7 => Ok(19),
+            number => Err(Error::InvalidVariant(number)),
+        }
+    }
+
+    /// Return the bit position of the exception pending bit in the SHCSR register
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    fn shcsr_pending_shift<E: ExceptionNumber>(exception: E) -> Result<u32> {
+        match exception.number() {
+            4 => Ok(13),
+            5 => Ok(14),
+            6 => Ok(12),
+            #[cfg(armv8m_main)]
+            7 => Ok(20),
+            11 => Ok(15),
+            number => Err(Error::InvalidVariant(number)),
         }
     }
 
@@ -1053,15 +1183,26 @@ impl SCB {
     /// * `UsageFault`
     /// * `SecureFault` (can only be enabled from Secure state)
     ///
-    /// Calling this function with any other exception will do nothing.
+    /// Calling this function with any other exception will do nothing. Use [`SCB::try_enable`] to
+    /// detect this case.
     #[inline]
     #[cfg(not(any(armv6m, armv8m_base)))]
-    pub fn enable(&mut self, exception: Exception) {
-        if let Some(shift) = SCB::shcsr_enable_shift(exception) {
-            // The mutable reference to SCB makes sure that only this code is currently modifying
-            // the register.
-            unsafe { self.shcsr.modify(|value| value | (1 << shift)) }
-        }
+    pub fn enable<E: ExceptionNumber>(&mut self, exception: E) {
+        let _ = self.try_enable(exception);
+    }
+
+    /// Enable the exception
+    ///
+    /// Same as [`SCB::enable`], but returns [`Error::InvalidVariant`] with the exception number if
+    /// `exception` can not be enabled.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn try_enable<E: ExceptionNumber>(&mut self, exception: E) -> Result<()> {
+        let shift = SCB::shcsr_enable_shift(exception)?;
+        // The mutable reference to SCB makes sure that only this code is currently modifying
+        // the register.
+        unsafe { self.shcsr.modify(|value| value | (1 << shift)) }
+        Ok(())
     }
 
     /// Disable the exception
@@ -1074,15 +1215,26 @@ impl SCB {
     /// * `UsageFault`
     /// * `SecureFault` (can not be changed from Non-secure state)
     ///
-    /// Calling this function with any other exception will do nothing.
+    /// Calling this function with any other exception will do nothing. Use [`SCB::try_disable`] to
+    /// detect this case.
     #[inline]
     #[cfg(not(any(armv6m, armv8m_base)))]
-    pub fn disable(&mut self, exception: Exception) {
-        if let Some(shift) = SCB::shcsr_enable_shift(exception) {
-            // The mutable reference to SCB makes sure that only this code is currently modifying
-            // the register.
-            unsafe { self.shcsr.modify(|value| value & !(1 << shift)) }
-        }
+    pub fn disable<E: ExceptionNumber>(&mut self, exception: E) {
+        let _ = self.try_disable(exception);
+    }
+
+    /// Disable the exception
+    ///
+    /// Same as [`SCB::disable`], but returns [`Error::InvalidVariant`] with the exception number if
+    /// `exception` can not be disabled.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn try_disable<E: ExceptionNumber>(&mut self, exception: E) -> Result<()> {
+        let shift = SCB::shcsr_enable_shift(exception)?;
+        // The mutable reference to SCB makes sure that only this code is currently modifying
+        // the register.
+        unsafe { self.shcsr.modify(|value| value & !(1 << shift)) }
+        Ok(())
     }
 
     /// Check if an exception is enabled
@@ -1093,14 +1245,303 @@ impl SCB {
     /// * `UsageFault`
     /// * `SecureFault` (can not be read from Non-secure state)
     ///
-    /// Calling this function with any other exception will read `false`.
+    /// Calling this function with any other exception will read `false`. Use
+    /// [`SCB::try_is_enabled`] to detect this case.
     #[inline]
     #[cfg(not(any(armv6m, armv8m_base)))]
-    pub fn is_enabled(&self, exception: Exception) -> bool {
-        if let Some(shift) = SCB::shcsr_enable_shift(exception) {
-            (self.shcsr.read() & (1 << shift)) > 0
-        } else {
-            false
+    pub fn is_enabled<E: ExceptionNumber>(&self, exception: E) -> bool {
+        self.try_is_enabled(exception).unwrap_or(false)
+    }
+
+    /// Check if an exception is enabled
+    ///
+    /// Same as [`SCB::is_enabled`], but returns [`Error::InvalidVariant`] with the exception number
+    /// if `exception` has no enable bit.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn try_is_enabled<E: ExceptionNumber>(&self, exception: E) -> Result<bool> {
+        let shift = SCB::shcsr_enable_shift(exception)?;
+        Ok((self.shcsr.read() & (1 << shift)) > 0)
+    }
+
+    /// Forces the exception into pending state
+    ///
+    /// This function is only allowed on the following exceptions:
+    /// * `MemoryManagement`
+    /// * `BusFault`
+    /// * `UsageFault`
+    /// * `SecureFault` (can only be pended from Secure state)
+    /// * `SVCall`
+    ///
+    /// Calling this function with any other exception will do nothing. Use [`SCB::try_pend`] to
+    /// detect this case. `PendSV` and `SysTick` are pended through [`SCB::set_pendsv`] and
+    /// [`SCB::set_pendst`].
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn pend<E: ExceptionNumber>(&mut self, exception: E) {
+        let _ = self.try_pend(exception);
+    }
+
+    /// Forces the exception into pending state
+    ///
+    /// Same as [`SCB::pend`], but returns [`Error::InvalidVariant`] with the exception number if
+    /// `exception` has no pending bit.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn try_pend<E: ExceptionNumber>(&mut self, exception: E) -> Result<()> {
+        let shift = SCB::shcsr_pending_shift(exception)?;
+        // The mutable reference to SCB makes sure that only this code is currently modifying
+        // the register.
+        unsafe { self.shcsr.modify(|value| value | (1 << shift)) }
+        Ok(())
+    }
+
+    /// Clears the pending state of the exception
+    ///
+    /// This function is only allowed on the same exceptions as [`SCB::pend`].
+    ///
+    /// Calling this function with any other exception will do nothing. Use [`SCB::try_unpend`]
+    /// to detect this case.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn unpend<E: ExceptionNumber>(&mut self, exception: E) {
+        let _ = self.try_unpend(exception);
+    }
+
+    /// Clears the pending state of the exception
+    ///
+    /// Same as [`SCB::unpend`], but returns [`Error::InvalidVariant`] with the exception number if
+    /// `exception` has no pending bit.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn try_unpend<E: ExceptionNumber>(&mut self, exception: E) -> Result<()> {
+        let shift = SCB::shcsr_pending_shift(exception)?;
+        // The mutable reference to SCB makes sure that only this code is currently modifying
+        // the register.
+        unsafe { self.shcsr.modify(|value| value & !(1 << shift)) }
+        Ok(())
+    }
+
+    /// Check if an exception is pending
+    ///
+    /// This function is only allowed on the same exceptions as [`SCB::pend`].
+    ///
+    /// Calling this function with any other exception will read `false`. Use
+    /// [`SCB::try_is_pending`] to detect this case.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn is_pending<E: ExceptionNumber>(&self, exception: E) -> bool {
+        self.try_is_pending(exception).unwrap_or(false)
+    }
+
+    /// Check if an exception is pending
+    ///
+    /// Same as [`SCB::is_pending`], but returns [`Error::InvalidVariant`] with the exception number
+    /// if `exception` has no pending bit.
+    #[inline]
+    #[cfg(not(any(armv6m, armv8m_base)))]
+    pub fn try_is_pending<E: ExceptionNumber>(&self, exception: E) -> Result<bool> {
+        let shift = SCB::shcsr_pending_shift(exception)?;
+        Ok((self.shcsr.read() & (1 << shift)) > 0)
+    }
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+bitfield! {
+    /// Configurable Fault Status Register
+    ///
+    /// Combines the MemManage (MMFSR), BusFault (BFSR) and UsageFault (UFSR) status registers.
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct Cfsr(u32);
+    impl Debug;
+    /// MemManage: instruction access violation
+    pub iaccviol, _: 0;
+    /// MemManage: data access violation, MMFAR holds the faulting address
+    pub daccviol, _: 1;
+    /// MemManage: fault on unstacking for a return from exception
+    pub munstkerr, _: 3;
+    /// MemManage: fault on stacking for exception entry
+    pub mstkerr, _: 4;
+    /// MemManage: fault during floating-point lazy state preservation
+    pub mlsperr, _: 5;
+    /// MemManage: MMFAR holds a valid fault address
+    pub mmarvalid, _: 7;
+    /// BusFault: instruction bus error
+    pub ibuserr, _: 8;
+    /// BusFault: precise data bus error, BFAR holds the faulting address
+    pub preciserr, _: 9;
+    /// BusFault: imprecise data bus error
+    pub impreciserr, _: 10;
+    /// BusFault: fault on unstacking for a return from exception
+    pub unstkerr, _: 11;
+    /// BusFault: fault on stacking for exception entry
+    pub stkerr, _: 12;
+    /// BusFault: fault during floating-point lazy state preservation
+    pub lsperr, _: 13;
+    /// BusFault: BFAR holds a valid fault address
+    pub bfarvalid, _: 15;
+    /// UsageFault: undefined instruction
+    pub undefinstr, _: 16;
+    /// UsageFault: invalid state, such as an attempt to switch to the ARM state
+    pub invstate, _: 17;
+    /// UsageFault: invalid EXC_RETURN value loaded into the PC
+    pub invpc, _: 18;
+    /// UsageFault: access to a coprocessor that is disabled or not present
+    pub nocp, _: 19;
+    /// UsageFault: stack overflow detected by a stack limit register (ARMv8-M only)
+    pub stkof, _: 20;
+    /// UsageFault: unaligned access while unaligned trapping is enabled
+    pub unaligned, _: 24;
+    /// UsageFault: integer division by zero while division by zero trapping is enabled
+    pub divbyzero, _: 25;
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+bitfield! {
+    /// HardFault Status Register
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct Hfsr(u32);
+    impl Debug;
+    /// Bus error on a vector read during exception processing
+    pub vecttbl, _: 1;
+    /// A configurable fault was escalated to HardFault
+    pub forced, _: 30;
+    /// A debug event occurred while halting debug was disabled
+    pub debugevt, _: 31;
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+bitfield! {
+    /// Debug Fault Status Register
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct Dfsr(u32);
+    impl Debug;
+    /// Halt request debug event
+    pub halted, _: 0;
+    /// Breakpoint debug event
+    pub bkpt, _: 1;
+    /// DWT debug event
+    pub dwttrap, _: 2;
+    /// Vector catch debug event
+    pub vcatch, _: 3;
+    /// External debug request debug event
+    pub external, _: 4;
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+impl Cfsr {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+impl Hfsr {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+impl Dfsr {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+/// Decoded fault status registers, as returned by [`SCB::fault_status`]
+#[cfg(not(any(armv6m, armv8m_base)))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FaultStatus {
+    /// MemManage, BusFault and UsageFault causes
+    pub cfsr: Cfsr,
+    /// HardFault causes
+    pub hfsr: Hfsr,
+    /// Debug event causes
+    pub dfsr: Dfsr,
+    /// Address of the MemManage fault, if `cfsr.mmarvalid()` is set
+    pub mmfar: Option<u32>,
+    /// Address of the BusFault, if `cfsr.bfarvalid()` is set
+    pub bfar: Option<u32>,
+    /// Auxiliary Fault Status, implementation defined
+    pub afsr: u32,
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+impl FaultStatus {
+    /// Decodes raw values of the CFSR, HFSR, DFSR, MMFAR, BFAR and AFSR registers.
+    #[inline]
+    pub fn from_registers(
+        cfsr: u32,
+        hfsr: u32,
+        dfsr: u32,
+        mmfar: u32,
+        bfar: u32,
+        afsr: u32,
+    ) -> Self {
+        let cfsr = Cfsr(cfsr);
+
+        FaultStatus {
+            cfsr,
+            hfsr: Hfsr(hfsr),
+            dfsr: Dfsr(dfsr),
+            mmfar: if cfsr.mmarvalid() { Some(mmfar) } else { None },
+            bfar: if cfsr.bfarvalid() { Some(bfar) } else { None },
+            afsr,
+        }
+    }
+
+    /// Returns `true` if no fault or debug event is recorded.
+    #[inline]
+    pub fn is_clear(&self) -> bool {
+        self.cfsr.0 == 0 && self.hfsr.0 == 0 && self.dfsr.0 == 0
+    }
+}
+
+#[cfg(not(any(armv6m, armv8m_base)))]
+impl SCB {
+    /// Reads and decodes the fault status registers.
+    #[inline]
+    pub fn fault_status() -> FaultStatus {
+        // NOTE(unsafe) atomic reads with no side effects
+        unsafe {
+            let scb = &*Self::PTR;
+
+            FaultStatus::from_registers(
+                scb.cfsr.read(),
+                scb.hfsr.read(),
+                scb.dfsr.read(),
+                scb.mmfar.read(),
+                scb.bfar.read(),
+                scb.afsr.read(),
+            )
+        }
+    }
+
+    /// Clears the sticky fault status bits reported in `status`.
+    ///
+    /// The CFSR, HFSR and DFSR bits are write-one-to-clear, so only the bits set in `status` are
+    /// cleared: faults recorded after `status` was read are preserved.
+    #[inline]
+    pub fn clear_fault_status(&mut self, status: &FaultStatus) {
+        unsafe {
+            self.cfsr.write(status.cfsr.0);
+            self.hfsr.write(status.hfsr.0);
+            self.dfsr.write(status.dfsr.0);
         }
     }
 }
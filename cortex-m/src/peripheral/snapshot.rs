@@ -0,0 +1,739 @@
+//! Snapshots of the core peripherals
+//!
+//! A [`Snapshot`] holds the raw contents of the configuration and status registers of the SCB,
+//! NVIC, SysTick, DWT, MPU and CPUID blocks. It can be:
+//!
+//! - captured on the device with [`Snapshot::capture`], and written back with
+//!   [`Snapshot::restore`];
+//! - encoded to a fixed little-endian byte dump with [`Snapshot::to_bytes`], cheap to send from a
+//!   device, and decoded on the host with [`Snapshot::from_bytes`];
+//! - serialized with `serde`, for example to compare a device's configuration against a golden
+//!   JSON reference with [`Snapshot::diff`], which ignores the status registers and counters.
+//!
+//! Registers that are not implemented by the architecture the snapshot was captured on read as
+//! zero.
+//!
+//! ```
+//! use cortex_m::peripheral::snapshot::Snapshot;
+//!
+//! let mut golden = Snapshot::default();
+//! golden.scb.vtor = 0x0800_0000;
+//!
+//! // e.g. received from the device
+//! let dump = golden.to_bytes();
+//! let mut device = Snapshot::from_bytes(&dump).unwrap();
+//! device.nvic.iser[0] = 1 << 5;
+//!
+//! let mut differences = 0;
+//! golden.diff(&device, |d| {
+//!     assert_eq!(d.register, "NVIC.ISER");
+//!     assert_eq!(d.index, Some(0));
+//!     differences += 1;
+//! });
+//! assert_eq!(differences, 1);
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::nvic::{self, NVIC_WORDS};
+use super::scb::{SCB_AIRCR_PRIGROUP_MASK, SCB_AIRCR_VECTKEY};
+use super::syst::{SYST_CSR_COUNTFLAG, SYST_CSR_ENABLE};
+use super::{CPUID, DWT, MPU, NVIC, SCB, SYST};
+use cortex_m_types::result::{Error, Result};
+
+/// First word of a byte dump, "SNP" followed by the format version.
+const SNAPSHOT_MAGIC: u32 = 0x534E_5001;
+
+/// Number of 32-bit words of a byte dump, including the magic word.
+const SNAPSHOT_WORDS: usize = 1 + 16 + 16 + 172 + 4 + 56 + 53;
+
+/// Number of DWT comparators and MPU regions recorded in a snapshot.
+const SNAPSHOT_SLOTS: usize = 16;
+
+/// System handler enable bits of SHCSR, on all architectures.
+const SHCSR_ENABLES: u32 = 0xF << 16;
+
+/// Snapshot of the core peripherals
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// CPUID registers
+    pub cpuid: CpuidSnapshot,
+    /// System Control Block registers
+    pub scb: ScbSnapshot,
+    /// Nested Vectored Interrupt Controller registers
+    pub nvic: NvicSnapshot,
+    /// SysTick registers
+    pub syst: SystSnapshot,
+    /// Data Watchpoint and Trace unit registers
+    pub dwt: DwtSnapshot,
+    /// Memory Protection Unit registers
+    pub mpu: MpuSnapshot,
+}
+
+/// CPUID registers
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CpuidSnapshot {
+    pub base: u32,
+    pub pfr: [u32; 2],
+    pub dfr: u32,
+    pub afr: u32,
+    pub mmfr: [u32; 4],
+    pub isar: [u32; 5],
+    pub clidr: u32,
+    pub ctr: u32,
+}
+
+/// System Control Block registers
+///
+/// `shpr` holds SHPR1 to SHPR3 as words, on all architectures.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ScbSnapshot {
+    pub icsr: u32,
+    pub vtor: u32,
+    pub aircr: u32,
+    pub scr: u32,
+    pub ccr: u32,
+    pub shpr: [u32; 3],
+    pub shcsr: u32,
+    pub cfsr: u32,
+    pub hfsr: u32,
+    pub dfsr: u32,
+    pub mmfar: u32,
+    pub bfar: u32,
+    pub afsr: u32,
+    pub cpacr: u32,
+}
+
+/// Nested Vectored Interrupt Controller registers
+///
+/// `ipr` holds the interrupt priority registers as words, on all architectures.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NvicSnapshot {
+    pub iser: [u32; 16],
+    pub ispr: [u32; 16],
+    pub iabr: [u32; 16],
+    #[serde(with = "words")]
+    pub ipr: [u32; 124],
+}
+
+impl Default for NvicSnapshot {
+    #[inline]
+    fn default() -> Self {
+        NvicSnapshot {
+            iser: [0; 16],
+            ispr: [0; 16],
+            iabr: [0; 16],
+            ipr: [0; 124],
+        }
+    }
+}
+
+impl NvicSnapshot {
+    /// Reads the registers of `nvic`.
+    pub(crate) fn read(&mut self, nvic: &nvic::RegisterBlock) {
+        for i in 0..NVIC_WORDS {
+            self.iser[i] = nvic.iser[i].read();
+            self.ispr[i] = nvic.ispr[i].read();
+            #[cfg(not(armv6m))]
+            {
+                self.iabr[i] = nvic.iabr[i].read();
+            }
+        }
+        #[cfg(not(armv6m))]
+        for (i, reg) in nvic.ipr.iter().enumerate() {
+            self.ipr[i / 4] |= u32::from(reg.read()) << (8 * (i % 4));
+        }
+        #[cfg(armv6m)]
+        for (word, reg) in self.ipr.iter_mut().zip(nvic.ipr.iter()) {
+            *word = reg.read();
+        }
+    }
+
+    /// Writes the enables and priorities to `nvic`.
+    ///
+    /// # Safety
+    ///
+    /// See [`Snapshot::restore`].
+    pub(crate) unsafe fn write(&self, nvic: &nvic::RegisterBlock) {
+        for i in 0..NVIC_WORDS {
+            nvic.icer[i].write(!self.iser[i]);
+            nvic.iser[i].write(self.iser[i]);
+        }
+        #[cfg(not(armv6m))]
+        for (i, reg) in nvic.ipr.iter().enumerate() {
+            reg.write((self.ipr[i / 4] >> (8 * (i % 4))) as u8);
+        }
+        #[cfg(armv6m)]
+        for (reg, word) in nvic.ipr.iter().zip(self.ipr.iter()) {
+            reg.write(*word);
+        }
+    }
+}
+
+/// SysTick registers
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SystSnapshot {
+    pub csr: u32,
+    pub rvr: u32,
+    pub cvr: u32,
+    pub calib: u32,
+}
+
+/// Data Watchpoint and Trace unit registers
+///
+/// Only the comparators reported by `DWT_CTRL.NUMCOMP` are captured.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DwtSnapshot {
+    pub ctrl: u32,
+    pub cyccnt: u32,
+    pub cpicnt: u32,
+    pub exccnt: u32,
+    pub sleepcnt: u32,
+    pub lsucnt: u32,
+    pub foldcnt: u32,
+    pub pcsr: u32,
+    pub comparators: [DwtComparatorSnapshot; SNAPSHOT_SLOTS],
+}
+
+/// DWT comparator registers
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DwtComparatorSnapshot {
+    pub comp: u32,
+    pub mask: u32,
+    pub function: u32,
+}
+
+/// Memory Protection Unit registers
+///
+/// Only the regions reported by `MPU_TYPE.DREGION` are captured. `mair` is only implemented on
+/// ARMv8-M.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MpuSnapshot {
+    #[serde(rename = "type")]
+    pub _type: u32,
+    pub ctrl: u32,
+    pub rnr: u32,
+    pub regions: [MpuRegionSnapshot; SNAPSHOT_SLOTS],
+    pub mair: [u32; 2],
+}
+
+/// MPU region registers
+///
+/// `rasr` is only implemented on ARMv6-M and ARMv7-M, `rlar` on ARMv8-M.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MpuRegionSnapshot {
+    pub rbar: u32,
+    pub rasr: u32,
+    pub rlar: u32,
+}
+
+/// A register that differs between two snapshots
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Difference {
+    /// Name of the register, such as `"SCB.VTOR"` or `"MPU.RBAR"`
+    pub register: &'static str,
+    /// Index of the register, for register arrays (or of the comparator or region)
+    pub index: Option<usize>,
+    /// Value in the snapshot `diff` was called on
+    pub left: u32,
+    /// Value in the snapshot passed to `diff`
+    pub right: u32,
+}
+
+impl Snapshot {
+    /// Size in bytes of the dump produced by [`Snapshot::to_bytes`]
+    pub const SIZE: usize = SNAPSHOT_WORDS * 4;
+
+    /// Captures the registers of the core peripherals.
+    ///
+    /// Reading SysTick's CSR clears its COUNTFLAG bit. The MPU region number register is
+    /// temporarily changed to read all the regions, in a critical section.
+    #[allow(clippy::missing_inline_in_public_items)]
+    pub fn capture() -> Self {
+        let mut snapshot = Snapshot::default();
+
+        // NOTE(unsafe) reads with no side effects, except where documented above
+        unsafe {
+            let cpuid = &*CPUID::PTR;
+            let s = &mut snapshot.cpuid;
+            s.base = cpuid.base.read();
+            #[cfg(not(armv6m))]
+            {
+                s.pfr = [cpuid.pfr[0].read(), cpuid.pfr[1].read()];
+                s.dfr = cpuid.dfr.read();
+                s.afr = cpuid.afr.read();
+                for (word, reg) in s.mmfr.iter_mut().zip(cpuid.mmfr.iter()) {
+                    *word = reg.read();
+                }
+                for (word, reg) in s.isar.iter_mut().zip(cpuid.isar.iter()) {
+                    *word = reg.read();
+                }
+                s.clidr = cpuid.clidr.read();
+                s.ctr = cpuid.ctr.read();
+            }
+
+            let scb = &*SCB::PTR;
+            let s = &mut snapshot.scb;
+            s.icsr = scb.icsr.read();
+            s.vtor = scb.vtor.read();
+            s.aircr = scb.aircr.read();
+            s.scr = scb.scr.read();
+            s.ccr = scb.ccr.read();
+            #[cfg(not(armv6m))]
+            for (i, reg) in scb.shpr.iter().enumerate() {
+                s.shpr[i / 4] |= u32::from(reg.read()) << (8 * (i % 4));
+            }
+            #[cfg(armv6m)]
+            {
+                s.shpr[1] = scb.shpr[0].read();
+                s.shpr[2] = scb.shpr[1].read();
+            }
+            s.shcsr = scb.shcsr.read();
+            #[cfg(not(armv6m))]
+            {
+                s.cfsr = scb.cfsr.read();
+                s.hfsr = scb.hfsr.read();
+                s.dfsr = scb.dfsr.read();
+                s.mmfar = scb.mmfar.read();
+                s.bfar = scb.bfar.read();
+                s.afsr = scb.afsr.read();
+                s.cpacr = scb.cpacr.read();
+            }
+
+            snapshot.nvic.read(&*NVIC::PTR);
+
+            let syst = &*SYST::PTR;
+            snapshot.syst = SystSnapshot {
+                csr: syst.csr.read(),
+                rvr: syst.rvr.read(),
+                cvr: syst.cvr.read(),
+                calib: syst.calib.read(),
+            };
+
+            let dwt = &*DWT::PTR;
+            let s = &mut snapshot.dwt;
+            s.ctrl = dwt.ctrl.read();
+            #[cfg(not(armv6m))]
+            {
+                s.cyccnt = dwt.cyccnt.read();
+                s.cpicnt = dwt.cpicnt.read();
+                s.exccnt = dwt.exccnt.read();
+                s.sleepcnt = dwt.sleepcnt.read();
+                s.lsucnt = dwt.lsucnt.read();
+                s.foldcnt = dwt.foldcnt.read();
+            }
+            s.pcsr = dwt.pcsr.read();
+            let comparators = usize::from(DWT::num_comp()).min(dwt.c.len());
+            for (word, reg) in s.comparators.iter_mut().zip(&dwt.c[..comparators]) {
+                *word = DwtComparatorSnapshot {
+                    comp: reg.comp.read(),
+                    mask: reg.mask.read(),
+                    function: reg.function.read(),
+                };
+            }
+        }
+
+        crate::interrupt::free(|_| {
+            // NOTE(unsafe) RNR is restored before leaving the critical section
+            unsafe {
+                let mpu = &*MPU::PTR;
+                let s = &mut snapshot.mpu;
+                s._type = mpu._type.read();
+                s.ctrl = mpu.ctrl.read();
+                s.rnr = mpu.rnr.read();
+                for (i, region) in s.regions.iter_mut().take(mpu_regions(s._type)).enumerate() {
+                    mpu.rnr.write(i as u32);
+                    region.rbar = mpu.rbar.read();
+                    #[cfg(not(armv8m))]
+                    {
+                        region.rasr = mpu.rasr.read();
+                    }
+                    #[cfg(armv8m)]
+                    {
+                        region.rlar = mpu.rlar.read();
+                    }
+                }
+                #[cfg(armv8m)]
+                {
+                    s.mair = [mpu.mair[0].read(), mpu.mair[1].read()];
+                }
+                mpu.rnr.write(s.rnr);
+            }
+        });
+
+        snapshot
+    }
+
+    /// Writes the configuration registers of the snapshot back to the core peripherals.
+    ///
+    /// This restores the SCB configuration (VTOR, the AIRCR priority grouping, SCR, CCR, the
+    /// system handler priorities and enables, CPACR), the NVIC enables and priorities, SysTick,
+    /// the DWT control and comparators, and the MPU regions. Status registers, pending and active
+    /// states and counters are left untouched.
+    ///
+    /// # Safety
+    ///
+    /// This reconfigures interrupts, exceptions and memory protection as a whole, which can break
+    /// any assumption of the running code about them.
+    #[allow(clippy::missing_inline_in_public_items)]
+    pub unsafe fn restore(&self) {
+        let scb = &*SCB::PTR;
+        let s = &self.scb;
+        scb.vtor.write(s.vtor);
+        scb.aircr
+            .write(SCB_AIRCR_VECTKEY | (s.aircr & SCB_AIRCR_PRIGROUP_MASK));
+        scb.scr.write(s.scr);
+        scb.ccr.write(s.ccr);
+        #[cfg(not(armv6m))]
+        for (i, reg) in scb.shpr.iter().enumerate() {
+            reg.write((s.shpr[i / 4] >> (8 * (i % 4))) as u8);
+        }
+        #[cfg(armv6m)]
+        {
+            scb.shpr[0].write(s.shpr[1]);
+            scb.shpr[1].write(s.shpr[2]);
+        }
+        #[cfg(not(any(armv6m, armv8m_base)))]
+        {
+            #[cfg(not(armv8m_main))]
+            const ENABLES: u32 = 0x7 << 16;
+            #[cfg(armv8m_main)]
+            const ENABLES: u32 = 0xF << 16;
+
+            scb.shcsr
+                .modify(|shcsr| (shcsr & !ENABLES) | (s.shcsr & ENABLES));
+            scb.cpacr.write(s.cpacr);
+        }
+
+        self.nvic.write(&*NVIC::PTR);
+
+        let syst = &*SYST::PTR;
+        let s = &self.syst;
+        syst.csr.write(s.csr & !SYST_CSR_ENABLE);
+        syst.rvr.write(s.rvr);
+        syst.cvr.write(s.cvr);
+        syst.csr.write(s.csr);
+
+        let dwt = &*DWT::PTR;
+        let s = &self.dwt;
+        dwt.ctrl.write(s.ctrl);
+        let comparators = usize::from(DWT::num_comp()).min(dwt.c.len());
+        for (reg, word) in dwt.c[..comparators].iter().zip(s.comparators.iter()) {
+            reg.comp.write(word.comp);
+            reg.mask.write(word.mask);
+            reg.function.write(word.function);
+        }
+
+        let mpu = &*MPU::PTR;
+        let s = &self.mpu;
+        mpu.ctrl.write(0);
+        crate::asm::dsb();
+        crate::asm::isb();
+        for (i, region) in s.regions.iter().take(mpu_regions(s._type)).enumerate() {
+            mpu.rnr.write(i as u32);
+            #[cfg(not(armv8m))]
+            {
+                // Clear VALID so that RNR selects the region
+                mpu.rbar.write(region.rbar & !(1 << 4));
+                mpu.rasr.write(region.rasr);
+            }
+            #[cfg(armv8m)]
+            {
+                mpu.rbar.write(region.rbar);
+                mpu.rlar.write(region.rlar);
+            }
+        }
+        #[cfg(armv8m)]
+        {
+            mpu.mair[0].write(s.mair[0]);
+            mpu.mair[1].write(s.mair[1]);
+        }
+        mpu.rnr.write(s.rnr);
+        mpu.ctrl.write(s.ctrl);
+        crate::asm::dsb();
+        crate::asm::isb();
+    }
+
+    /// Encodes the snapshot as a byte dump.
+    ///
+    /// The dump is a magic word followed by all the registers, in declaration order, as
+    /// little-endian 32-bit words.
+    #[allow(clippy::missing_inline_in_public_items)]
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        let mut chunks = bytes.chunks_exact_mut(4);
+
+        chunks
+            .next()
+            .unwrap()
+            .copy_from_slice(&SNAPSHOT_MAGIC.to_le_bytes());
+
+        let mut copy = *self;
+        copy.for_each_register(&mut |_, _, word| {
+            chunks.next().unwrap().copy_from_slice(&word.to_le_bytes());
+        });
+        debug_assert!(chunks.next().is_none());
+
+        bytes
+    }
+
+    /// Decodes a byte dump produced by [`Snapshot::to_bytes`].
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if `bytes` is not exactly [`Snapshot::SIZE`] bytes
+    /// long, and [`Error::InvalidFieldVariant`] if it does not start with the expected magic
+    /// word.
+    #[allow(clippy::missing_inline_in_public_items)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::SIZE {
+            return Err(Error::IndexOutOfBounds {
+                index: bytes.len(),
+                min: Self::SIZE,
+                max: Self::SIZE,
+            });
+        }
+
+        let mut words = bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+
+        let magic = words.next().unwrap();
+        if magic != SNAPSHOT_MAGIC {
+            return Err(Error::InvalidFieldVariant {
+                field: "MAGIC",
+                value: magic as usize,
+            });
+        }
+
+        let mut snapshot = Snapshot::default();
+        snapshot.for_each_register(&mut |_, _, word| *word = words.next().unwrap());
+
+        Ok(snapshot)
+    }
+
+    /// Calls `f` for every configuration register whose value differs between `self` and
+    /// `other`.
+    ///
+    /// Registers that change while the device runs are skipped, so that a capture can be compared
+    /// against a golden reference:
+    ///
+    /// - the SCB status registers: ICSR, CFSR, HFSR, DFSR, MMFAR, BFAR and AFSR;
+    /// - the NVIC pending and active states: ISPR and IABR;
+    /// - the SysTick current value, CVR;
+    /// - the DWT counters and PC sample: CYCCNT, CPICNT, EXCCNT, SLEEPCNT, LSUCNT, FOLDCNT and
+    ///   PCSR;
+    /// - the MPU region number, RNR.
+    ///
+    /// Only the system handler enables of SCB.SHCSR are compared, and the COUNTFLAG bit of
+    /// SYST.CSR is ignored. The differences report the full register values.
+    #[allow(clippy::missing_inline_in_public_items)]
+    pub fn diff<F: FnMut(Difference)>(&self, other: &Self, mut f: F) {
+        let left = self.to_bytes();
+        let right = other.to_bytes();
+        let word = |bytes: &[u8; Self::SIZE], n: usize| {
+            u32::from_le_bytes([
+                bytes[n * 4],
+                bytes[n * 4 + 1],
+                bytes[n * 4 + 2],
+                bytes[n * 4 + 3],
+            ])
+        };
+
+        // skip the magic word
+        let mut n = 1;
+        let mut copy = *self;
+        copy.for_each_register(&mut |register, index, _| {
+            let (left, right) = (word(&left, n), word(&right, n));
+            let bits = configuration_bits(register);
+            if left & bits != right & bits {
+                f(Difference {
+                    register,
+                    index,
+                    left,
+                    right,
+                });
+            }
+            n += 1;
+        });
+    }
+
+    /// Calls `f` on every register, in declaration order.
+    fn for_each_register(&mut self, f: &mut dyn FnMut(&'static str, Option<usize>, &mut u32)) {
+        fn array(
+            f: &mut dyn FnMut(&'static str, Option<usize>, &mut u32),
+            name: &'static str,
+            words: &mut [u32],
+        ) {
+            for (i, word) in words.iter_mut().enumerate() {
+                f(name, Some(i), word);
+            }
+        }
+
+        let s = &mut self.cpuid;
+        f("CPUID.BASE", None, &mut s.base);
+        array(f, "CPUID.PFR", &mut s.pfr);
+        f("CPUID.DFR", None, &mut s.dfr);
+        f("CPUID.AFR", None, &mut s.afr);
+        array(f, "CPUID.MMFR", &mut s.mmfr);
+        array(f, "CPUID.ISAR", &mut s.isar);
+        f("CPUID.CLIDR", None, &mut s.clidr);
+        f("CPUID.CTR", None, &mut s.ctr);
+
+        let s = &mut self.scb;
+        f("SCB.ICSR", None, &mut s.icsr);
+        f("SCB.VTOR", None, &mut s.vtor);
+        f("SCB.AIRCR", None, &mut s.aircr);
+        f("SCB.SCR", None, &mut s.scr);
+        f("SCB.CCR", None, &mut s.ccr);
+        array(f, "SCB.SHPR", &mut s.shpr);
+        f("SCB.SHCSR", None, &mut s.shcsr);
+        f("SCB.CFSR", None, &mut s.cfsr);
+        f("SCB.HFSR", None, &mut s.hfsr);
+        f("SCB.DFSR", None, &mut s.dfsr);
+        f("SCB.MMFAR", None, &mut s.mmfar);
+        f("SCB.BFAR", None, &mut s.bfar);
+        f("SCB.AFSR", None, &mut s.afsr);
+        f("SCB.CPACR", None, &mut s.cpacr);
+
+        let s = &mut self.nvic;
+        array(f, "NVIC.ISER", &mut s.iser);
+        array(f, "NVIC.ISPR", &mut s.ispr);
+        array(f, "NVIC.IABR", &mut s.iabr);
+        array(f, "NVIC.IPR", &mut s.ipr);
+
+        let s = &mut self.syst;
+        f("SYST.CSR", None, &mut s.csr);
+        f("SYST.RVR", None, &mut s.rvr);
+        f("SYST.CVR", None, &mut s.cvr);
+        f("SYST.CALIB", None, &mut s.calib);
+
+        let s = &mut self.dwt;
+        f("DWT.CTRL", None, &mut s.ctrl);
+        f("DWT.CYCCNT", None, &mut s.cyccnt);
+        f("DWT.CPICNT", None, &mut s.cpicnt);
+        f("DWT.EXCCNT", None, &mut s.exccnt);
+        f("DWT.SLEEPCNT", None, &mut s.sleepcnt);
+        f("DWT.LSUCNT", None, &mut s.lsucnt);
+        f("DWT.FOLDCNT", None, &mut s.foldcnt);
+        f("DWT.PCSR", None, &mut s.pcsr);
+        for (i, c) in s.comparators.iter_mut().enumerate() {
+            f("DWT.COMP", Some(i), &mut c.comp);
+            f("DWT.MASK", Some(i), &mut c.mask);
+            f("DWT.FUNCTION", Some(i), &mut c.function);
+        }
+
+        let s = &mut self.mpu;
+        f("MPU.TYPE", None, &mut s._type);
+        f("MPU.CTRL", None, &mut s.ctrl);
+        f("MPU.RNR", None, &mut s.rnr);
+        for (i, r) in s.regions.iter_mut().enumerate() {
+            f("MPU.RBAR", Some(i), &mut r.rbar);
+            f("MPU.RASR", Some(i), &mut r.rasr);
+            f("MPU.RLAR", Some(i), &mut r.rlar);
+        }
+        array(f, "MPU.MAIR", &mut s.mair);
+    }
+}
+
+/// Returns the bits of `register` that [`Snapshot::diff`] compares.
+fn configuration_bits(register: &str) -> u32 {
+    match register {
+        // status, pending and active states
+        "SCB.ICSR" | "SCB.CFSR" | "SCB.HFSR" | "SCB.DFSR" | "SCB.MMFAR" | "SCB.BFAR"
+        | "SCB.AFSR" | "NVIC.ISPR" | "NVIC.IABR" => 0,
+        // counters and samples
+        "SYST.CVR" | "DWT.CYCCNT" | "DWT.CPICNT" | "DWT.EXCCNT" | "DWT.SLEEPCNT" | "DWT.LSUCNT"
+        | "DWT.FOLDCNT" | "DWT.PCSR" => 0,
+        // only selects the region accessed through RBAR, RASR and RLAR
+        "MPU.RNR" => 0,
+        "SCB.SHCSR" => SHCSR_ENABLES,
+        "SYST.CSR" => !SYST_CSR_COUNTFLAG,
+        _ => !0,
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Snapshot {
+    /// Serializes the snapshot as JSON.
+    #[inline]
+    pub fn to_json(&self) -> serde_json::Result<alloc::string::String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes a snapshot from JSON.
+    #[inline]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl fmt::Display for Difference {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{}[{}]", self.register, index)?,
+            None => write!(f, "{}", self.register)?,
+        }
+        write!(f, ": {:#010x} != {:#010x}", self.left, self.right)
+    }
+}
+
+/// Number of MPU regions reported by MPU_TYPE, capped to the snapshot capacity.
+fn mpu_regions(_type: u32) -> usize {
+    (((_type >> 8) & 0xFF) as usize).min(SNAPSHOT_SLOTS)
+}
+
+/// `serde` support for word arrays longer than 32 elements.
+mod words {
+    use super::*;
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        words: &[u32; N],
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(words.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> core::result::Result<[u32; N], D::Error> {
+        struct WordsVisitor<const N: usize>(PhantomData<[u32; N]>);
+
+        impl<'de, const N: usize> Visitor<'de> for WordsVisitor<N> {
+            type Value = [u32; N];
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "an array of {} words", N)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> core::result::Result<Self::Value, A::Error> {
+                let mut words = [0; N];
+                for (i, word) in words.iter_mut().enumerate() {
+                    *word = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u32>()?.is_some() {
+                    return Err(de::Error::invalid_length(N + 1, &self));
+                }
+                Ok(words)
+            }
+        }
+
+        deserializer.deserialize_seq(WordsVisitor(PhantomData))
+    }
+}
@@ -14,9 +14,12 @@
 //! let syst_value: u32 = SYST::get_current();
 //! ```
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use volatile_register::{RO, RW};
 
 use crate::peripheral::SYST;
+use cortex_m_types::result::{Error, Result};
 
 /// Register block
 #[repr(C)]
@@ -33,6 +36,8 @@ pub struct RegisterBlock {
 
 /// SysTick clock source
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SystClkSource {
     /// Core-provided clock
     Core,
@@ -42,10 +47,10 @@ pub enum SystClkSource {
 
 const SYST_COUNTER_MASK: u32 = 0x00ff_ffff;
 
-const SYST_CSR_ENABLE: u32 = 1 << 0;
+pub(crate) const SYST_CSR_ENABLE: u32 = 1 << 0;
 const SYST_CSR_TICKINT: u32 = 1 << 1;
 const SYST_CSR_CLKSOURCE: u32 = 1 << 2;
-const SYST_CSR_COUNTFLAG: u32 = 1 << 16;
+pub(crate) const SYST_CSR_COUNTFLAG: u32 = 1 << 16;
 
 const SYST_CALIB_SKEW: u32 = 1 << 30;
 const SYST_CALIB_NOREF: u32 = 1 << 31;
@@ -196,4 +201,21 @@ impl SYST {
     pub fn set_reload(&mut self, value: u32) {
         unsafe { self.rvr.write(value) }
     }
+
+    /// Sets reload value
+    ///
+    /// Same as [`SYST::set_reload`], but returns [`Error::InvalidFieldValue`] instead of writing
+    /// `value` if it is not between `1` and `0x00ffffff`.
+    #[inline]
+    pub fn try_set_reload(&mut self, value: u32) -> Result<()> {
+        if value == 0 || value & !SYST_COUNTER_MASK != 0 {
+            return Err(Error::InvalidFieldValue {
+                field: "RELOAD",
+                value: value as usize,
+                bitmask: SYST_COUNTER_MASK as usize,
+            });
+        }
+        self.set_reload(value);
+        Ok(())
+    }
 }
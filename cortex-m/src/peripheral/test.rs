@@ -14,6 +14,72 @@ fn cpuid() {
     assert_eq!(address(&cpuid.csselr), 0xE000_ED84);
 }
 
+#[test]
+fn cpuid_info() {
+    use crate::peripheral::cpuid::{CpuInfo, FpuSupport, Implementer, MveSupport, Part};
+
+    // Cortex-M4F r0p1
+    let m4f = CpuInfo::from_registers(
+        0x410F_C241,
+        0x0000_0200,
+        0x0111_1131,
+        0x1011_0021,
+        0x1100_0011,
+    );
+    assert_eq!(m4f.implementer, Implementer::Arm);
+    assert_eq!(m4f.part, Part::CortexM4);
+    assert_eq!((m4f.variant, m4f.revision), (0, 1));
+    assert!(m4f.features.dsp);
+    assert_eq!(m4f.features.fpu, FpuSupport::Single);
+    assert_eq!(m4f.features.mve, MveSupport::None);
+    assert!(!m4f.features.trustzone);
+
+    // Cortex-M3 r2p1, without DSP nor FPU
+    let m3 = CpuInfo::from_registers(0x412F_C231, 0x0000_0200, 0x0111_1110, 0, 0);
+    assert_eq!(m3.part, Part::CortexM3);
+    assert_eq!((m3.variant, m3.revision), (2, 1));
+    assert!(!m3.features.dsp);
+    assert_eq!(m3.features.fpu, FpuSupport::None);
+
+    // Cortex-M55 with TrustZone, double-precision FPU and floating-point MVE
+    let m55 = CpuInfo::from_registers(
+        0x411F_D220,
+        0x0000_0210,
+        0x0111_1131,
+        0x1011_0221,
+        0x1210_0211,
+    );
+    assert_eq!(m55.part, Part::CortexM55);
+    assert_eq!(m55.features.fpu, FpuSupport::Double);
+    assert_eq!(m55.features.mve, MveSupport::IntegerAndFloat);
+    assert!(m55.features.trustzone);
+
+    let other = CpuInfo::from_registers(0x4400_ABC0, 0, 0, 0, 0);
+    assert_eq!(other.implementer, Implementer::Other(0x44));
+    assert_eq!(other.part, Part::Other(0xABC));
+}
+
+#[test]
+fn cpuid_try_select_cache() {
+    use crate::peripheral::cpuid::CsselrCacheType;
+    use crate::peripheral::CPUID;
+    use crate::result::Error;
+    use core::marker::PhantomData;
+
+    let mut cpuid = CPUID {
+        _marker: PhantomData,
+    };
+
+    assert_eq!(
+        cpuid.try_select_cache(8, CsselrCacheType::DataOrUnified),
+        Err(Error::IndexOutOfBounds {
+            index: 8,
+            min: 0,
+            max: 7,
+        })
+    );
+}
+
 #[test]
 fn dcb() {
     let dcb = unsafe { &*crate::peripheral::DCB::PTR };
@@ -107,6 +173,78 @@ fn mpu() {
     assert_eq!(address(&mpu.rasr_a3), 0xE000EDB8);
 }
 
+#[test]
+fn mpu_region() {
+    use crate::peripheral::mpu::{AccessPermission, MemoryAttributes, MpuRegion};
+    use crate::result::Error;
+
+    let region = MpuRegion::new(0x2000_0000, 128 << 10)
+        .unwrap()
+        .access(AccessPermission::UnprivilegedReadOnly)
+        .execute_never(true)
+        .disable_subregions(0b1000_0001)
+        .unwrap();
+    assert_eq!(region.size(), 0x2_0000);
+    assert_eq!(region.rbar(3), 0x2000_0013);
+    // XN, AP = 0b010, TEX = 0b001, C, B, SRD = 0x81, SIZE = 16, ENABLE
+    assert_eq!(region.rasr(), 0x120B_8121);
+
+    let device = MpuRegion::new(0x4000_0000, 512 << 20)
+        .unwrap()
+        .attributes(MemoryAttributes::DEVICE);
+    // AP = 0b011, S, B, SIZE = 28, ENABLE
+    assert_eq!(device.rasr(), 0x0305_0039);
+
+    assert_eq!(
+        MpuRegion::new(0x2000_0000, 48),
+        Err(Error::InvalidValue {
+            value: 48,
+            bitmask: !0x1F_u32 as usize
+        })
+    );
+    assert_eq!(
+        MpuRegion::new(0x2000_0100, 0x1000),
+        Err(Error::InvalidFieldValue {
+            field: "ADDR",
+            value: 0x2000_0100,
+            bitmask: !0xFFF_u32 as usize
+        })
+    );
+    assert!(MpuRegion::new(0x2000_0000, 128)
+        .unwrap()
+        .disable_subregions(1)
+        .is_err());
+    assert!(MemoryAttributes::from_fields(8, false, false, false).is_err());
+}
+
+#[test]
+fn mpu_check_regions() {
+    use crate::peripheral::mpu::check_regions;
+    use crate::result::Error;
+
+    assert_eq!(check_regions(0, 8, 8), Ok(()));
+    assert_eq!(check_regions(7, 1, 8), Ok(()));
+    assert_eq!(
+        check_regions(7, 2, 8),
+        Err(Error::IndexOutOfBounds {
+            index: 8,
+            min: 0,
+            max: 7
+        })
+    );
+    // RBAR selects at most 16 regions
+    assert_eq!(
+        check_regions(16, 1, 32),
+        Err(Error::IndexOutOfBounds {
+            index: 16,
+            min: 0,
+            max: 31
+        })
+    );
+    // an empty range, for example past the top region
+    assert_eq!(check_regions(8, 0, 8), Ok(()));
+}
+
 #[test]
 fn nvic() {
     let nvic = unsafe { &*crate::peripheral::NVIC::PTR };
@@ -121,6 +259,242 @@ fn nvic() {
     assert_eq!(address(&nvic.stir), 0xE000EF00);
 }
 
+#[test]
+fn nvic_priority_encoding() {
+    use crate::peripheral::nvic::{priority_from_hw, priority_to_hw};
+    use crate::result::{Error, Result};
+    use crate::PriorityNumber;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    enum Priority {
+        P0 = 0,
+        P1 = 1,
+        P2 = 2,
+        P3 = 3,
+        P4 = 4,
+    }
+
+    unsafe impl PriorityNumber for Priority {
+        const MAX_PRIORITY_NUMBER: usize = Self::P4 as usize;
+
+        fn number(self) -> usize {
+            self as _
+        }
+
+        fn from_number(number: usize) -> Result<Self> {
+            match number {
+                0 => Ok(Priority::P0),
+                1 => Ok(Priority::P1),
+                2 => Ok(Priority::P2),
+                3 => Ok(Priority::P3),
+                4 => Ok(Priority::P4),
+                _ => Err(Error::InvalidVariant(number)),
+            }
+        }
+    }
+
+    // 3 priority bits are needed for levels 0..=4
+    assert_eq!(priority_to_hw(Priority::P4), 0x00);
+    assert_eq!(priority_to_hw(Priority::P3), 0x20);
+    assert_eq!(priority_to_hw(Priority::P0), 0x80);
+
+    assert_eq!(priority_from_hw(0x00), Ok(Priority::P4));
+    assert_eq!(priority_from_hw(0x3F), Ok(Priority::P3));
+    assert_eq!(priority_from_hw(0x80), Ok(Priority::P0));
+    assert_eq!(
+        priority_from_hw::<Priority>(0xA0),
+        Err(Error::InvalidVariant(0xA0))
+    );
+}
+
+#[test]
+fn nvic_interrupt_set() {
+    use crate::peripheral::nvic::InterruptSet;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types::InterruptNumber)]
+    enum Interrupt {
+        I0 = 0,
+        I5 = 5,
+        I31 = 31,
+        I32 = 32,
+        I40 = 40,
+    }
+
+    let mut set: InterruptSet<Interrupt> = [Interrupt::I0, Interrupt::I31, Interrupt::I40]
+        .into_iter()
+        .collect();
+    assert_eq!(set.words(), &[0x8000_0001, 0x0000_0100]);
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(Interrupt::I31));
+    assert!(!set.contains(Interrupt::I32));
+
+    set.remove(Interrupt::I31);
+    set.insert(Interrupt::I32);
+    assert!(set
+        .iter()
+        .eq([Interrupt::I0, Interrupt::I32, Interrupt::I40]));
+
+    let other: InterruptSet<Interrupt> = [Interrupt::I5, Interrupt::I40].into_iter().collect();
+    assert_eq!(set.union(&other).len(), 4);
+    assert!(set.intersection(&other).iter().eq([Interrupt::I40]));
+    assert_eq!(set.difference(&other).len(), 2);
+    assert!(InterruptSet::<Interrupt>::new().is_empty());
+}
+
+#[test]
+fn nvic_interrupt_handles() {
+    use crate::peripheral::NVIC;
+    use crate::result::Error;
+    use core::marker::PhantomData;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, cortex_m_types::InterruptNumber)]
+    enum Interrupt {
+        Uart0 = 0,
+        Timer1 = 33,
+    }
+
+    let nvic = NVIC {
+        _marker: PhantomData,
+    };
+    let mut handles = nvic.into_handles::<Interrupt>();
+
+    let uart = handles.take(Interrupt::Uart0).unwrap();
+    assert_eq!(uart.interrupt(), Interrupt::Uart0);
+    let timer = handles.take(Interrupt::Timer1).unwrap();
+    assert_eq!(
+        handles.take(Interrupt::Uart0).unwrap_err(),
+        Error::AlreadyTaken
+    );
+    assert_eq!(handles.taken().len(), 2);
+
+    handles.release(uart);
+    assert!(!handles.taken().contains(Interrupt::Uart0));
+    assert!(handles.taken().contains(timer.interrupt()));
+    assert!(handles.take(Interrupt::Uart0).is_ok());
+}
+
+#[test]
+fn logical_priority() {
+    use crate::priority::{HwPriority, LogicalPriority, PriorityGrouping};
+    use crate::result::Error;
+
+    // 3 implemented bits: levels 0..=7, 7 being the most urgent
+    let top = LogicalPriority::new(7, 3).unwrap();
+    let bottom = LogicalPriority::new(0, 3).unwrap();
+    assert_eq!(top.to_hw(), 0x00);
+    assert_eq!(bottom.to_hw(), 0xE0);
+    assert_eq!(LogicalPriority::from_hw(0xE0, 3), Ok(bottom));
+    assert_eq!(LogicalPriority::from_hw(0x3F, 3).unwrap().level(), 6);
+    assert_eq!(
+        LogicalPriority::new(8, 3),
+        Err(Error::InvalidFieldValue {
+            field: "LEVEL",
+            value: 8,
+            bitmask: 7
+        })
+    );
+    assert!(LogicalPriority::new(0, 9).is_err());
+    assert_eq!(LogicalPriority::new(0xFF, 8).unwrap().to_hw(), 0x00);
+
+    // 2 preemption bits, of which the 3 implemented bits leave 1 to the sub-priority
+    let grouping = PriorityGrouping::with_preempt_bits(2).unwrap();
+    assert_eq!(grouping.prigroup(), 5);
+    assert_eq!(grouping.split(3), (2, 1));
+    let prio = LogicalPriority::from_preempt_sub(2, 1, 3, grouping).unwrap();
+    assert_eq!(prio.level(), 0b101);
+    assert_eq!((prio.preempt(grouping), prio.sub(grouping)), (2, 1));
+    assert_eq!(HwPriority::to_hw(prio), 0b010 << 5);
+    assert!(LogicalPriority::from_preempt_sub(4, 0, 3, grouping).is_err());
+    assert!(LogicalPriority::from_preempt_sub(0, 2, 3, grouping).is_err());
+
+    // all bits are sub-priority
+    let grouping = PriorityGrouping::from_prigroup(7).unwrap();
+    let prio = LogicalPriority::from_preempt_sub(0, 0x12, 8, grouping).unwrap();
+    assert_eq!((prio.preempt(grouping), prio.sub(grouping)), (0, 0x12));
+    assert!(PriorityGrouping::from_prigroup(8).is_err());
+}
+
+#[test]
+fn vector_table() {
+    use crate::result::{Error, Result};
+    use crate::vector_table::VectorTable;
+    use crate::ExceptionNumber;
+    use core::mem::{self, MaybeUninit};
+
+    #[derive(Clone, Copy, cortex_m_types::InterruptNumber)]
+    enum Interrupt {
+        I0 = 0,
+        I15 = 15,
+    }
+
+    #[derive(Clone, Copy)]
+    struct Exception(usize);
+
+    unsafe impl ExceptionNumber for Exception {
+        const MAX_EXCEPTION_NUMBER: usize = 15;
+
+        fn number(self) -> usize {
+            self.0
+        }
+
+        fn from_number(number: usize) -> Result<Self> {
+            Ok(Exception(number))
+        }
+    }
+
+    extern "C" fn handler() {}
+    let address = handler as *const () as usize;
+
+    let source: [usize; 32] = core::array::from_fn(|i| 0x100 + i);
+    let mut slot = MaybeUninit::<VectorTable<32>>::uninit();
+    let table = unsafe { VectorTable::init_from(&mut slot, source.as_ptr()) };
+
+    assert_eq!(table.entries(), &source);
+    assert_eq!(
+        table.entries().as_ptr() as usize % mem::align_of::<VectorTable<32>>(),
+        0
+    );
+    assert_eq!(VectorTable::<16>::ALIGN, 128);
+    assert_eq!(VectorTable::<40>::ALIGN, 2 * mem::size_of::<[usize; 32]>());
+
+    assert_eq!(table.register(Interrupt::I15, handler), 0x11F);
+    assert_eq!(table.handler(Interrupt::I15), address);
+    assert_eq!(table.handler(Interrupt::I0), 0x110);
+    assert_eq!(table.unregister(Interrupt::I15), address);
+    assert_eq!(table.handler(Interrupt::I15), 0x11F);
+
+    unsafe {
+        assert_eq!(table.register_exception(Exception(11), handler), Ok(0x10B));
+        assert_eq!(table.entries()[11], address);
+        assert_eq!(table.unregister_exception(Exception(11)), Ok(address));
+        assert_eq!(
+            table.register_exception(Exception(1), handler),
+            Err(Error::IndexOutOfBounds {
+                index: 1,
+                min: 2,
+                max: 15
+            })
+        );
+    }
+    assert_eq!(table.entries(), &source);
+
+    // The second table is only aligned on 128 bytes, less than its size
+    #[repr(C, align(1024))]
+    struct Slots([MaybeUninit<VectorTable<40>>; 2]);
+
+    let mut slots = Slots([MaybeUninit::uninit(), MaybeUninit::uninit()]);
+    let table = unsafe { VectorTable::init_from(&mut slots.0[1], source.as_ptr()) };
+    let address = table.entries().as_ptr() as usize;
+    assert_ne!(address % VectorTable::<40>::ALIGN, 0);
+    assert_eq!(
+        table.check_alignment(),
+        Err(Error::InvalidValue {
+            value: address,
+            bitmask: !(VectorTable::<40>::ALIGN as u32 - 1) as usize,
+        })
+    );
+}
+
 #[test]
 fn scb() {
     let scb = unsafe { &*crate::peripheral::SCB::PTR };
@@ -141,6 +515,61 @@ fn scb() {
     assert_eq!(address(&scb.cpacr), 0xE000_ED88);
 }
 
+#[test]
+fn scb_fault_status() {
+    use crate::peripheral::scb::FaultStatus;
+
+    // precise data bus error with a valid BFAR, escalated to HardFault
+    let status =
+        FaultStatus::from_registers(0x0000_8200, 0x4000_0000, 0, 0xE000_0000, 0x2002_0000, 0);
+    assert!(status.cfsr.preciserr());
+    assert!(status.cfsr.bfarvalid());
+    assert!(!status.cfsr.impreciserr());
+    assert!(!status.cfsr.mmarvalid());
+    assert!(status.hfsr.forced());
+    assert_eq!(status.mmfar, None);
+    assert_eq!(status.bfar, Some(0x2002_0000));
+    assert!(!status.is_clear());
+
+    // data access violation, division by zero and stack overflow
+    let status = FaultStatus::from_registers(0x0210_0082, 0, 0b10, 0x1000, 0, 0);
+    assert!(status.cfsr.daccviol());
+    assert!(status.cfsr.divbyzero());
+    assert!(status.cfsr.stkof());
+    assert!(!status.cfsr.undefinstr());
+    assert!(status.dfsr.bkpt());
+    assert_eq!(status.mmfar, Some(0x1000));
+    assert_eq!(status.cfsr.bits(), 0x0210_0082);
+
+    assert!(FaultStatus::from_registers(0, 0, 0, 0x1000, 0x2000, 0).is_clear());
+}
+
+#[test]
+fn scb_vect_active() {
+    use crate::peripheral::scb::{Exception, VectActive};
+    use crate::result::Error;
+    use crate::ExceptionNumber;
+
+    assert_eq!(Exception::SVCall.number(), 11);
+    assert_eq!(Exception::SysTick.irqn(), -1);
+    assert_eq!(Exception::from_number(14), Ok(Exception::PendSV));
+    assert_eq!(Exception::from_number(8), Err(Error::InvalidVariant(8)));
+
+    assert_eq!(VectActive::<Exception>::from(0), Ok(VectActive::ThreadMode));
+    assert_eq!(
+        VectActive::from(3),
+        Ok(VectActive::Exception(Exception::HardFault))
+    );
+    assert_eq!(
+        VectActive::<Exception>::from(300),
+        Ok(VectActive::Interrupt { irqn: 284 })
+    );
+    assert_eq!(
+        VectActive::<Exception>::from(9),
+        Err(Error::InvalidVariant(9))
+    );
+}
+
 #[test]
 fn syst() {
     let syst = unsafe { &*crate::peripheral::SYST::PTR };
@@ -151,6 +580,101 @@ fn syst() {
     assert_eq!(address(&syst.calib), 0xE000_E01C);
 }
 
+#[test]
+fn syst_try_set_reload() {
+    use crate::peripheral::SYST;
+    use crate::result::Error;
+    use core::marker::PhantomData;
+
+    let mut syst = SYST {
+        _marker: PhantomData,
+    };
+
+    assert_eq!(
+        syst.try_set_reload(0),
+        Err(Error::InvalidFieldValue {
+            field: "RELOAD",
+            value: 0,
+            bitmask: 0x00ff_ffff,
+        })
+    );
+    assert_eq!(
+        syst.try_set_reload(0x0100_0000),
+        Err(Error::InvalidFieldValue {
+            field: "RELOAD",
+            value: 0x0100_0000,
+            bitmask: 0x00ff_ffff,
+        })
+    );
+}
+
+#[test]
+fn sau() {
+    let sau = unsafe { &*crate::peripheral::SAU::PTR };
+
+    assert_eq!(address(&sau.ctrl), 0xE000_EDD0);
+    assert_eq!(address(&sau._type), 0xE000_EDD4);
+    assert_eq!(address(&sau.rnr), 0xE000_EDD8);
+    assert_eq!(address(&sau.rbar), 0xE000_EDDC);
+    assert_eq!(address(&sau.rlar), 0xE000_EDE0);
+    assert_eq!(address(&sau.sfsr), 0xE000_EDE4);
+    assert_eq!(address(&sau.sfar), 0xE000_EDE8);
+}
+
+#[test]
+fn sau_try_set_region() {
+    use crate::peripheral::sau::{check_region_number, SauError, SauRegion, SauRegionAttribute};
+    use crate::peripheral::SAU;
+    use crate::result::Error;
+    use core::marker::PhantomData;
+
+    let mut sau = SAU {
+        _marker: PhantomData,
+    };
+
+    let misaligned_base = SauRegion {
+        base_address: 0x2000_0010,
+        limit_address: 0x2000_03FF,
+        attribute: SauRegionAttribute::NonSecure,
+    };
+    assert_eq!(
+        sau.try_set_region(0, misaligned_base),
+        Err(Error::InvalidFieldValue {
+            field: "BADDR",
+            value: 0x2000_0010,
+            bitmask: 0xFFFF_FFE0,
+        })
+    );
+
+    let misaligned_limit = SauRegion {
+        base_address: 0x2000_0000,
+        limit_address: 0x2000_03F0,
+        attribute: SauRegionAttribute::NonSecureCallable,
+    };
+    assert!(matches!(
+        sau.set_region(0, misaligned_limit),
+        Err(SauError::WrongLimitAddress)
+    ));
+
+    assert_eq!(check_region_number(7, 8), Ok(()));
+    assert_eq!(
+        check_region_number(8, 8),
+        Err(Error::IndexOutOfBounds {
+            index: 8,
+            min: 0,
+            max: 7,
+        })
+    );
+    assert_eq!(
+        check_region_number(0, 0),
+        Err(Error::IndexOutOfBounds {
+            index: 0,
+            min: 0,
+            max: 0,
+        })
+    );
+}
+
 #[test]
 fn tpiu() {
     let tpiu = unsafe { &*crate::peripheral::TPIU::PTR };
@@ -165,6 +689,178 @@ fn tpiu() {
     assert_eq!(address(&tpiu._type), 0xE004_0FC8);
 }
 
+#[test]
+fn take_for_core() {
+    use crate::multicore::{check_current_core, is_current_core, CurrentCore};
+    use crate::peripheral::Peripherals;
+    use crate::result::{Error, Result};
+    use crate::CoreIdNumber;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    enum CoreId {
+        H0 = 0,
+        H1 = 1,
+    }
+
+    unsafe impl CoreIdNumber for CoreId {
+        const MAX_CORE_ID_NUMBER: usize = 1;
+
+        fn number(self) -> usize {
+            self as usize
+        }
+
+        fn from_number(number: usize) -> Result<Self> {
+            match number {
+                0 => Ok(CoreId::H0),
+                1 => Ok(CoreId::H1),
+                _ => Err(Error::InvalidVariant(number)),
+            }
+        }
+    }
+
+    unsafe impl CurrentCore for CoreId {
+        fn current() -> Self {
+            CoreId::H1
+        }
+    }
+
+    assert!(is_current_core(CoreId::H1));
+    assert!(!is_current_core(CoreId::H0));
+    assert_eq!(check_current_core(CoreId::H1), Ok(()));
+    assert_eq!(
+        Peripherals::take_for_core(CoreId::H0).err(),
+        Some(Error::WrongCore {
+            expected: 0,
+            current: 1
+        })
+    );
+
+    // `take` claims the peripherals of all the cores
+    assert!(Peripherals::take().is_some());
+    assert!(Peripherals::take().is_none());
+    assert_eq!(
+        Peripherals::take_for_core(CoreId::H1).err(),
+        Some(Error::AlreadyTaken)
+    );
+}
+
+#[test]
+fn take_for_core_transitions() {
+    use super::{claim, claim_core, ALL_CORES, PER_CORE, UNCLAIMED};
+    use crate::result::Error;
+    use core::sync::atomic::{AtomicBool, AtomicU8};
+
+    // per-core claims
+    let ownership = AtomicU8::new(UNCLAIMED);
+    let taken = [AtomicBool::new(false), AtomicBool::new(false)];
+    assert_eq!(claim_core(&ownership, &taken[1]), Ok(()));
+    assert_eq!(claim_core(&ownership, &taken[1]), Err(Error::AlreadyTaken));
+    assert_eq!(claim_core(&ownership, &taken[0]), Ok(()));
+    assert_eq!(claim_core(&ownership, &taken[0]), Err(Error::AlreadyTaken));
+    // `take` fails after a per-core claim
+    assert_eq!(claim(&ownership, ALL_CORES), PER_CORE);
+    assert_eq!(claim(&ownership, ALL_CORES), PER_CORE);
+
+    // claim of all the cores
+    let ownership = AtomicU8::new(UNCLAIMED);
+    let taken = [AtomicBool::new(false), AtomicBool::new(false)];
+    assert_eq!(claim(&ownership, ALL_CORES), UNCLAIMED);
+    assert_eq!(claim(&ownership, ALL_CORES), ALL_CORES);
+    assert_eq!(claim_core(&ownership, &taken[0]), Err(Error::AlreadyTaken));
+    assert_eq!(claim_core(&ownership, &taken[1]), Err(Error::AlreadyTaken));
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn snapshot_nvic_round_trip() {
+    use crate::peripheral::nvic::RegisterBlock;
+    use crate::peripheral::snapshot::NvicSnapshot;
+
+    // NOTE(unsafe) the register block only holds volatile cells of integers
+    let device: RegisterBlock = unsafe { core::mem::zeroed() };
+    unsafe {
+        device.iser[0].write(1 << 5);
+        // IRQs 480 to 495 live in the 16th word
+        device.iser[15].write(1 << 15);
+        device.ispr[15].write(1 << 3);
+        device.ipr[495].write(0xE0);
+    }
+
+    let mut snapshot = NvicSnapshot::default();
+    snapshot.read(&device);
+    assert_eq!(snapshot.iser[0], 1 << 5);
+    assert_eq!(snapshot.iser[15], 1 << 15);
+    assert_eq!(snapshot.ispr[15], 1 << 3);
+    assert_eq!(snapshot.ipr[123], 0xE000_0000);
+
+    let restored: RegisterBlock = unsafe { core::mem::zeroed() };
+    unsafe { snapshot.write(&restored) };
+    assert_eq!(restored.iser[15].read(), 1 << 15);
+    assert_eq!(restored.icer[15].read(), !(1 << 15));
+    assert_eq!(restored.ipr[495].read(), 0xE0);
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn snapshot_bytes() {
+    use crate::peripheral::snapshot::Snapshot;
+    use crate::result::Error;
+
+    let mut snapshot = Snapshot::default();
+    snapshot.cpuid.base = 0x410F_C241;
+    snapshot.nvic.ipr[123] = 0xE0E0_E0E0;
+    snapshot.mpu.mair = [0x44, 0xFF];
+
+    let bytes = snapshot.to_bytes();
+    assert_eq!(&bytes[4..8], &0x410F_C241u32.to_le_bytes());
+    assert_eq!(&bytes[Snapshot::SIZE - 4..], &0xFFu32.to_le_bytes());
+    assert_eq!(Snapshot::from_bytes(&bytes), Ok(snapshot));
+
+    assert_eq!(
+        Snapshot::from_bytes(&bytes[4..]),
+        Err(Error::IndexOutOfBounds {
+            index: Snapshot::SIZE - 4,
+            min: Snapshot::SIZE,
+            max: Snapshot::SIZE
+        })
+    );
+
+    let mut corrupted = bytes;
+    corrupted[0] = 0;
+    assert!(matches!(
+        Snapshot::from_bytes(&corrupted),
+        Err(Error::InvalidFieldVariant { field: "MAGIC", .. })
+    ));
+
+    let mut other = snapshot;
+    other.mpu.regions[3].rbar = 0x2000_0000;
+    // status registers and counters are not compared
+    other.scb.icsr = 1 << 28;
+    other.scb.cfsr = 1 << 17;
+    other.scb.shcsr |= 1 << 8;
+    other.nvic.ispr[0] = 1;
+    other.syst.csr |= 1 << 16;
+    other.syst.cvr = 1234;
+    other.dwt.cyccnt = 5678;
+    other.mpu.rnr = 3;
+    // but the system handler enables are
+    other.scb.shcsr |= 1 << 16;
+    let mut differences = [None; 3];
+    let mut n = 0;
+    snapshot.diff(&other, |d| {
+        differences[n] = Some((d.register, d.index, d.left, d.right));
+        n += 1;
+    });
+    assert_eq!(
+        differences,
+        [
+            Some(("SCB.SHCSR", None, 0, 0x0001_0100)),
+            Some(("MPU.RBAR", Some(3), 0, 0x2000_0000)),
+            None
+        ]
+    );
+}
+
 fn address<T>(r: *const T) -> usize {
     r as usize
 }
@@ -0,0 +1,192 @@
+//! Low-power sleep
+//!
+//! Entering a sleep mode takes a few steps that are easy to get wrong: the sleep bits of the SCR
+//! must be set up, a `DSB` must complete the outstanding memory accesses (including the SCR write)
+//! before `WFI` or `WFE`, and an `ISB` after waking up makes sure the following instructions are
+//! not fetched before the wake-up. [`sleep`], [`sleep_with`] and [`sleep_masked`] take care of
+//! them.
+//!
+//! # Waking up with interrupts masked
+//!
+//! An idle loop must not go to sleep if an interrupt handler made work available between the
+//! check and `WFI`, or it sleeps until the next interrupt. [`sleep_masked`] checks whether to
+//! sleep with interrupts masked by PRIMASK. A pending interrupt still wakes the core up, but its
+//! handler only runs once PRIMASK is restored, after the post-sleep hook had a chance to restore
+//! the clocks.
+//!
+//! ```no_run
+//! use core::sync::atomic::{AtomicBool, Ordering};
+//! use cortex_m::peripheral::Peripherals;
+//! use cortex_m::power::{self, SleepHooks, SleepMode, WakeSource};
+//!
+//! static WORK: AtomicBool = AtomicBool::new(false);
+//!
+//! struct Clocks;
+//!
+//! impl SleepHooks for Clocks {
+//!     fn pre_sleep(&mut self, _mode: SleepMode) {
+//!         // switch to the low-power clock
+//!     }
+//!
+//!     fn post_sleep(&mut self, _mode: SleepMode) {
+//!         // restore the PLL
+//!     }
+//! }
+//!
+//! let mut p = Peripherals::take().unwrap();
+//! loop {
+//!     power::sleep_masked(
+//!         &mut p.SCB,
+//!         SleepMode::DeepSleep,
+//!         WakeSource::Interrupt,
+//!         &mut Clocks,
+//!         |_| !WORK.load(Ordering::Relaxed),
+//!     );
+//!     if WORK.swap(false, Ordering::Relaxed) {
+//!         // ..
+//!     }
+//! }
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::interrupt::{self, CriticalSection};
+use crate::peripheral::SCB;
+use crate::register::primask;
+
+/// Sleep mode, set up in the SCR before waiting
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SleepMode {
+    /// Sleep: the core clock is stopped (`SLEEPDEEP` and `SLEEPONEXIT` cleared).
+    Sleep,
+    /// Deep sleep: the device may also stop other clocks and power domains, as defined by the
+    /// vendor (`SLEEPDEEP` set).
+    DeepSleep,
+    /// Sleep, and go back to sleep on every return from an exception handler to Thread mode
+    /// (`SLEEPONEXIT` set), so the program only runs in handlers.
+    ///
+    /// The call does not return until a handler clears `SLEEPONEXIT` with
+    /// [`SCB::clear_sleeponexit`].
+    SleepOnExit,
+}
+
+/// What wakes the core up
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WakeSource {
+    /// Wait for interrupt (`WFI`): an interrupt that is enabled, or masked by PRIMASK only.
+    Interrupt,
+    /// Wait for event (`WFE`): an event, such as `SEV` from another core, or an interrupt that
+    /// would preempt. Returns immediately if the event register was already set.
+    Event,
+    /// Wait for event with `SEVONPEND` set: any interrupt becoming pending is also an event,
+    /// even a disabled one.
+    EventOnPend,
+}
+
+/// Vendor specific steps around sleep, such as switching clocks or power domains
+///
+/// Both hooks default to doing nothing, and `()` implements the trait with the defaults.
+pub trait SleepHooks {
+    /// Called right before waiting, with the SCR already set up.
+    #[inline(always)]
+    fn pre_sleep(&mut self, _mode: SleepMode) {}
+
+    /// Called right after waking up.
+    #[inline(always)]
+    fn post_sleep(&mut self, _mode: SleepMode) {}
+}
+
+impl SleepHooks for () {}
+
+/// Sleeps in `mode` until an interrupt.
+#[inline]
+pub fn sleep(scb: &mut SCB, mode: SleepMode) {
+    sleep_with(scb, mode, WakeSource::Interrupt, &mut ());
+}
+
+/// Sleeps in `mode` until `wake`, calling `hooks` around the wait.
+///
+/// When woken up by an interrupt, its handler may run before the post-sleep hook. Use
+/// [`sleep_masked`] to run the hook first.
+#[inline]
+pub fn sleep_with<H>(scb: &mut SCB, mode: SleepMode, wake: WakeSource, hooks: &mut H)
+where
+    H: SleepHooks,
+{
+    configure(scb, mode, wake);
+    hooks.pre_sleep(mode);
+    wait(wake);
+    hooks.post_sleep(mode);
+}
+
+/// Masks interrupts with PRIMASK, then sleeps in `mode` until `wake` if `f` returns `true`.
+///
+/// Returns what `f` returned. An interrupt that became pending, before or during the sleep, wakes
+/// the core up but its handler only runs when PRIMASK is restored, after the post-sleep hook.
+/// With [`WakeSource::Interrupt`] the core does not go to sleep if an interrupt is already
+/// pending; with [`WakeSource::EventOnPend`] only an interrupt becoming pending is an event.
+#[inline]
+pub fn sleep_masked<H, F>(
+    scb: &mut SCB,
+    mode: SleepMode,
+    wake: WakeSource,
+    hooks: &mut H,
+    f: F,
+) -> bool
+where
+    H: SleepHooks,
+    F: FnOnce(&CriticalSection) -> bool,
+{
+    let primask = primask::read();
+    interrupt::disable();
+
+    // NOTE(unsafe) interrupts are disabled until PRIMASK is restored
+    let sleep = f(unsafe { &CriticalSection::new() });
+    if sleep {
+        sleep_with(scb, mode, wake, hooks);
+    }
+
+    if primask.is_active() {
+        // NOTE(unsafe) interrupts were enabled on entry
+        unsafe { interrupt::enable() };
+    }
+    sleep
+}
+
+#[inline(always)]
+fn configure(scb: &mut SCB, mode: SleepMode, wake: WakeSource) {
+    match mode {
+        SleepMode::Sleep => {
+            scb.clear_sleepdeep();
+            scb.clear_sleeponexit();
+        }
+        SleepMode::DeepSleep => {
+            scb.set_sleepdeep();
+            scb.clear_sleeponexit();
+        }
+        SleepMode::SleepOnExit => {
+            scb.clear_sleepdeep();
+            scb.set_sleeponexit();
+        }
+    }
+    match wake {
+        WakeSource::EventOnPend => scb.set_sevonpend(),
+        WakeSource::Interrupt | WakeSource::Event => scb.clear_sevonpend(),
+    }
+}
+
+#[inline(always)]
+fn wait(wake: WakeSource) {
+    // Complete the SCR write and the outstanding memory accesses before waiting
+    crate::asm::dsb();
+    match wake {
+        WakeSource::Interrupt => crate::asm::wfi(),
+        WakeSource::Event | WakeSource::EventOnPend => crate::asm::wfe(),
+    }
+    crate::asm::isb();
+}
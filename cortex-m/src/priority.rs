@@ -0,0 +1,282 @@
+//! Logical priorities and priority grouping
+//!
+//! The NVIC and the SCB encode priorities in the most significant bits of a byte, and a lower
+//! value is more urgent. How many bits are implemented is device specific, and the priority
+//! grouping (`AIRCR.PRIGROUP`) splits them into a preemption priority, which decides whether an
+//! exception can preempt another, and a sub-priority, which only orders pending exceptions.
+//!
+//! [`LogicalPriority`] hides both details: a higher level is more urgent, like for
+//! [`PriorityNumber`], and it is converted to the hardware encoding using the number of
+//! implemented bits discovered with
+//! [`NVIC::implemented_priority_bits`](crate::peripheral::NVIC::implemented_priority_bits).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cortex_m::peripheral::{Peripherals, SCB};
+//! use cortex_m::priority::{LogicalPriority, PriorityGrouping};
+//!
+//! let mut p = Peripherals::take().unwrap();
+//! let bits = p.NVIC.implemented_priority_bits();
+//!
+//! // 2 bits of preemption priority, the rest is sub-priority
+//! let grouping = PriorityGrouping::with_preempt_bits(2).unwrap();
+//! # #[cfg(not(any(armv6m, armv8m_base)))]
+//! unsafe { p.SCB.set_priority_grouping(grouping) };
+//!
+//! let prio = LogicalPriority::from_preempt_sub(3, 0, bits, grouping).unwrap();
+//! # #[derive(Clone, Copy)]
+//! # enum Exception { SysTick = 15 }
+//! # unsafe impl cortex_m::ExceptionNumber for Exception {
+//! #     const MAX_EXCEPTION_NUMBER: usize = 15;
+//! #     fn number(self) -> usize { self as usize }
+//! #     fn from_number(n: usize) -> cortex_m::result::Result<Self> {
+//! #         Err(cortex_m::result::Error::InvalidVariant(n))
+//! #     }
+//! # }
+//! unsafe { p.SCB.set_priority(Exception::SysTick, prio) };
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::peripheral::nvic::priority_to_hw;
+use crate::PriorityNumber;
+use cortex_m_types::result::{Error, Result};
+
+/// A priority that can be written to the NVIC and SCB priority registers
+///
+/// This is implemented for all the [`PriorityNumber`] types, and for [`LogicalPriority`].
+pub trait HwPriority: Copy {
+    /// Returns the hardware encoding of the priority.
+    fn to_hw(self) -> u8;
+}
+
+impl<P: PriorityNumber> HwPriority for P {
+    #[inline]
+    fn to_hw(self) -> u8 {
+        priority_to_hw(self)
+    }
+}
+
+/// Priority grouping: how the priority bits are split between preemption priority and
+/// sub-priority
+///
+/// This is the `PRIGROUP` field of the `AIRCR` register. The sub-priority uses the
+/// `PRIGROUP + 1` least significant bits of the priority byte, and the preemption priority uses
+/// the remaining `7 - PRIGROUP` bits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PriorityGrouping {
+    prigroup: u8,
+}
+
+impl PriorityGrouping {
+    /// Creates a `PriorityGrouping` from the value of the `PRIGROUP` field.
+    ///
+    /// Returns [`Error::InvalidFieldValue`] if `prigroup` is greater than 7.
+    #[inline]
+    pub const fn from_prigroup(prigroup: u8) -> Result<Self> {
+        if prigroup > 7 {
+            return Err(Error::InvalidFieldValue {
+                field: "PRIGROUP",
+                value: prigroup as usize,
+                bitmask: 0x7,
+            });
+        }
+        Ok(PriorityGrouping { prigroup })
+    }
+
+    /// Creates a `PriorityGrouping` that leaves `bits` bits of the priority byte to the
+    /// preemption priority.
+    ///
+    /// Returns [`Error::InvalidValue`] if `bits` is greater than 7.
+    #[inline]
+    pub const fn with_preempt_bits(bits: u8) -> Result<Self> {
+        if bits > 7 {
+            return Err(Error::InvalidValue {
+                value: bits as usize,
+                bitmask: 0x7,
+            });
+        }
+        Ok(PriorityGrouping { prigroup: 7 - bits })
+    }
+
+    /// Returns the value of the `PRIGROUP` field.
+    #[inline]
+    pub const fn prigroup(self) -> u8 {
+        self.prigroup
+    }
+
+    /// Returns the number of bits of the priority byte used for the preemption priority.
+    #[inline]
+    pub const fn preempt_bits(self) -> u8 {
+        7 - self.prigroup
+    }
+
+    /// Returns how many of the `implemented` most significant priority bits are used for the
+    /// preemption priority and for the sub-priority.
+    #[inline]
+    pub const fn split(self, implemented: u8) -> (u8, u8) {
+        let preempt = if implemented < self.preempt_bits() {
+            implemented
+        } else {
+            self.preempt_bits()
+        };
+        (preempt, implemented - preempt)
+    }
+}
+
+/// A priority level where a higher level is more urgent
+///
+/// The level uses the `bits` priority bits implemented by the device, so it ranges from `0`, the
+/// least urgent level, to `2^bits - 1`, the most urgent one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogicalPriority {
+    bits: u8,
+    level: u8,
+}
+
+impl LogicalPriority {
+    /// Creates a logical priority `level` for a device implementing `bits` priority bits.
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if `bits` is not between 1 and 8, or
+    /// [`Error::InvalidFieldValue`] if `level` does not fit in `bits` bits.
+    #[inline]
+    pub const fn new(level: u8, bits: u8) -> Result<Self> {
+        if bits < 1 || bits > 8 {
+            return Err(Error::IndexOutOfBounds {
+                index: bits as usize,
+                min: 1,
+                max: 8,
+            });
+        }
+        let max = Self::field_max(bits);
+        if level > max {
+            return Err(Error::InvalidFieldValue {
+                field: "LEVEL",
+                value: level as usize,
+                bitmask: max as usize,
+            });
+        }
+        Ok(LogicalPriority { bits, level })
+    }
+
+    /// Creates a logical priority from a preemption priority and a sub-priority, for a device
+    /// implementing `bits` priority bits and using `grouping`.
+    ///
+    /// Like the level, a higher preemption priority or sub-priority is more urgent. Returns
+    /// [`Error::InvalidFieldValue`] if either value does not fit in its share of the bits.
+    #[inline]
+    pub const fn from_preempt_sub(
+        preempt: u8,
+        sub: u8,
+        bits: u8,
+        grouping: PriorityGrouping,
+    ) -> Result<Self> {
+        if bits < 1 || bits > 8 {
+            return Err(Error::IndexOutOfBounds {
+                index: bits as usize,
+                min: 1,
+                max: 8,
+            });
+        }
+        let (preempt_bits, sub_bits) = grouping.split(bits);
+        let preempt_max = Self::field_max(preempt_bits);
+        if preempt > preempt_max {
+            return Err(Error::InvalidFieldValue {
+                field: "PREEMPT",
+                value: preempt as usize,
+                bitmask: preempt_max as usize,
+            });
+        }
+        let sub_max = Self::field_max(sub_bits);
+        if sub > sub_max {
+            return Err(Error::InvalidFieldValue {
+                field: "SUB",
+                value: sub as usize,
+                bitmask: sub_max as usize,
+            });
+        }
+        let level = if sub_bits == 0 {
+            preempt
+        } else if preempt_bits == 0 {
+            sub
+        } else {
+            (preempt << sub_bits) | sub
+        };
+        Ok(LogicalPriority { bits, level })
+    }
+
+    /// Decodes the hardware encoding `hw` of a device implementing `bits` priority bits.
+    ///
+    /// The unimplemented low bits of `hw` are ignored. Returns [`Error::IndexOutOfBounds`] if
+    /// `bits` is not between 1 and 8.
+    #[inline]
+    pub const fn from_hw(hw: u8, bits: u8) -> Result<Self> {
+        if bits < 1 || bits > 8 {
+            return Err(Error::IndexOutOfBounds {
+                index: bits as usize,
+                min: 1,
+                max: 8,
+            });
+        }
+        let level = Self::field_max(bits) - (hw >> (8 - bits));
+        Ok(LogicalPriority { bits, level })
+    }
+
+    /// Returns the level, `2^bits - 1` being the most urgent.
+    #[inline]
+    pub const fn level(self) -> u8 {
+        self.level
+    }
+
+    /// Returns the number of implemented priority bits the level is based on.
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.bits
+    }
+
+    /// Returns the preemption priority under `grouping`, a higher value being more urgent.
+    #[inline]
+    pub const fn preempt(self, grouping: PriorityGrouping) -> u8 {
+        match grouping.split(self.bits) {
+            (0, _) => 0,
+            (_, sub_bits) => self.level >> sub_bits,
+        }
+    }
+
+    /// Returns the sub-priority under `grouping`, a higher value being more urgent.
+    #[inline]
+    pub const fn sub(self, grouping: PriorityGrouping) -> u8 {
+        let (_, sub_bits) = grouping.split(self.bits);
+        self.level & Self::field_max(sub_bits)
+    }
+
+    /// Returns the hardware encoding of the priority.
+    #[inline]
+    pub const fn to_hw(self) -> u8 {
+        let hw = Self::field_max(self.bits) - self.level;
+        if self.bits == 8 {
+            hw
+        } else {
+            hw << (8 - self.bits)
+        }
+    }
+
+    /// Returns the largest value of a `bits` bits wide field.
+    #[inline(always)]
+    const fn field_max(bits: u8) -> u8 {
+        (((1u16) << bits) - 1) as u8
+    }
+}
+
+impl HwPriority for LogicalPriority {
+    #[inline]
+    fn to_hw(self) -> u8 {
+        LogicalPriority::to_hw(self)
+    }
+}
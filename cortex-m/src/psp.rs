@@ -9,6 +9,8 @@ use core::{
     sync::atomic::{AtomicBool, Ordering},
 };
 
+use cortex_m_types::result::{Error, Result};
+
 /// Represents access to a [`Stack`]
 pub struct StackHandle(*mut u32, usize);
 
@@ -55,14 +57,58 @@ impl<const N: usize> Stack<N> {
     }
 
     /// Return the top of the stack
+    ///
+    /// # Panics
+    ///
+    /// Panics if a handle to this stack has already been taken. Use [`Stack::try_take_handle`]
+    /// to handle this case.
     pub fn take_handle(&self) -> StackHandle {
-        if self.taken.load(Ordering::Acquire) {
-            panic!("Cannot get two handles to one stack!");
+        match self.try_take_handle() {
+            Ok(handle) => handle,
+            Err(_) => panic!("Cannot get two handles to one stack!"),
+        }
+    }
+
+    /// Return the top of the stack
+    ///
+    /// Returns [`Error::AlreadyTaken`] if a handle to this stack has already been taken.
+    ///
+    /// ```rust
+    /// # use cortex_m::psp::Stack;
+    /// # use cortex_m::result::Error;
+    /// static PSP_STACK: Stack::<4096> = Stack::new();
+    ///
+    /// assert!(PSP_STACK.try_take_handle().is_ok());
+    /// assert_eq!(PSP_STACK.try_take_handle().err(), Some(Error::AlreadyTaken));
+    /// ```
+    pub fn try_take_handle(&self) -> Result<StackHandle> {
+        if self.claim() {
+            return Err(Error::AlreadyTaken);
         }
-        self.taken.store(true, Ordering::Release);
 
         let start = self.space.get() as *mut u32;
-        StackHandle(start, N)
+        Ok(StackHandle(start, N))
+    }
+
+    /// Marks the stack as taken, returning whether it already was.
+    #[cfg(target_has_atomic = "8")]
+    #[inline]
+    fn claim(&self) -> bool {
+        self.taken.swap(true, Ordering::AcqRel)
+    }
+
+    /// Marks the stack as taken, returning whether it already was.
+    ///
+    /// Without atomic read-modify-write instructions (ARMv6-M), the load and the store are made
+    /// atomic by masking interrupts.
+    #[cfg(not(target_has_atomic = "8"))]
+    #[inline]
+    fn claim(&self) -> bool {
+        crate::interrupt::free(|_| {
+            let taken = self.taken.load(Ordering::Acquire);
+            self.taken.store(true, Ordering::Release);
+            taken
+        })
     }
 }
 
@@ -105,3 +151,27 @@ pub fn switch_to_privileged_psp(mut psp_stack: StackHandle, function: extern "C"
         crate::asm::enter_privileged_psp(psp_stack.top(), function);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_take_handle_twice() {
+        let stack = Stack::<16>::new();
+
+        let mut handle = stack.try_take_handle().unwrap();
+        assert_eq!(stack.try_take_handle().err(), Some(Error::AlreadyTaken));
+        assert_eq!(handle.bottom(), stack.space.get() as *mut u32);
+        assert_eq!(handle.top(), unsafe { handle.bottom().add(16) });
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot get two handles to one stack!")]
+    fn take_handle_twice() {
+        let stack = Stack::<16>::new();
+
+        let _handle = stack.take_handle();
+        let _ = stack.take_handle();
+    }
+}
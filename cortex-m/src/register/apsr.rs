@@ -1,7 +1,12 @@
 //! Application Program Status Register
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Application Program Status Register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Apsr {
     bits: u32,
 }
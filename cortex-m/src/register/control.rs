@@ -1,7 +1,12 @@
 //! Control register
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Control register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Control {
     bits: u32,
 }
@@ -104,6 +109,8 @@ impl Control {
 
 /// Thread mode privilege level
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Npriv {
     /// Privileged
     Privileged,
@@ -127,6 +134,8 @@ impl Npriv {
 
 /// Currently active stack pointer
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Spsel {
     /// MSP is the current stack pointer
     Msp,
@@ -150,6 +159,8 @@ impl Spsel {
 
 /// Whether context floating-point is currently active
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Fpca {
     /// Floating-point context active.
     Active,
@@ -1,7 +1,12 @@
 //! Fault Mask Register
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// All exceptions are ...
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Faultmask {
     /// Active
     Active,
@@ -1,7 +1,12 @@
 //! Floating-point Status Control Register
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Floating-point Status Control Register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Fpscr {
     bits: u32,
 }
@@ -253,6 +258,8 @@ impl Fpscr {
 
 /// Rounding mode
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum RMode {
     /// Round to Nearest (RN) mode. This is the reset value.
     Nearest,
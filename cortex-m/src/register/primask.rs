@@ -4,9 +4,13 @@
 use core::arch::asm;
 #[cfg(cortex_m)]
 use core::sync::atomic::{compiler_fence, Ordering};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// All exceptions with configurable priority are ...
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Primask {
     /// Active
     Active,
@@ -0,0 +1,251 @@
+//! Reset cause tracking
+//!
+//! A [`ResetRecord`] kept in RAM that is not initialized at startup tells apart three kinds of
+//! resets:
+//!
+//! - a cold boot, after which the RAM holds garbage and the record is not valid;
+//! - a software reset requested with [`software_reset`] or [`local_reset`], which store a
+//!   reason code in the record first;
+//! - any other reset while the program was running, with the record still armed by
+//!   [`take_cause`]: typically the watchdog firing, but also the reset pin or a lockup.
+//!
+//! The record is stored in the `.uninit` section of `cortex-m-rt`, which is neither zeroed nor
+//! initialized at startup. With other runtimes, the `.uninit.cortex_m.RESET_RECORD` input section
+//! must be placed in a `NOLOAD` output section.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cortex_m::reset::{self, ResetCause};
+//!
+//! const REASON_UPDATE: u32 = 1;
+//!
+//! match reset::take_cause() {
+//!     ResetCause::ColdBoot => {}
+//!     ResetCause::Software(REASON_UPDATE) => {}
+//!     ResetCause::Software(_) => {}
+//!     ResetCause::Watchdog => {}
+//! }
+//!
+//! // ..
+//! reset::software_reset(REASON_UPDATE);
+//! ```
+
+use core::mem::MaybeUninit;
+use core::ptr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::peripheral::SCB;
+
+/// "RUN!": the program is running, no software reset was requested
+const MAGIC_RUNNING: u32 = 0x5255_4E21;
+/// "SWRS": a software reset was requested
+const MAGIC_SOFTWARE: u32 = 0x5357_5253;
+
+/// Cause of the last reset, as recorded in a [`ResetRecord`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResetCause {
+    /// The record is not valid: power-on reset, brown-out, or the record was never armed.
+    ColdBoot,
+    /// A software reset was requested with the given reason code.
+    Software(u32),
+    /// The record was armed, but the core was reset without a software reset request: watchdog,
+    /// reset pin, lockup, or a reset requested by other means such as a debugger.
+    Watchdog,
+}
+
+/// Reset record, meant to be stored in RAM that survives resets
+///
+/// The record is made of a magic word telling its state, the reason code, and a check word
+/// that guards the two against the garbage found in RAM after a cold boot.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct ResetRecord {
+    magic: u32,
+    reason: u32,
+    check: u32,
+}
+
+impl ResetRecord {
+    /// Creates a record that reads as a cold boot.
+    #[inline]
+    pub const fn new() -> Self {
+        ResetRecord {
+            magic: 0,
+            reason: 0,
+            check: 0,
+        }
+    }
+
+    /// Returns the cause of the reset this record was left in.
+    #[inline]
+    pub fn cause(&self) -> ResetCause {
+        // NOTE(unsafe) `self` is a valid record
+        unsafe { Self::read(self) }
+    }
+
+    /// Arms the record: until [`ResetRecord::set_reason`] is called, a reset reads as
+    /// [`ResetCause::Watchdog`].
+    #[inline]
+    pub fn arm(&mut self) {
+        // NOTE(unsafe) `self` is a valid record
+        unsafe { Self::write(self, MAGIC_RUNNING, 0) }
+    }
+
+    /// Records a software reset with the given reason code.
+    #[inline]
+    pub fn set_reason(&mut self, reason: u32) {
+        // NOTE(unsafe) `self` is a valid record
+        unsafe { Self::write(self, MAGIC_SOFTWARE, reason) }
+    }
+
+    /// Returns the cause of the reset this record was left in, and arms it.
+    #[inline]
+    pub fn take(&mut self) -> ResetCause {
+        // NOTE(unsafe) `self` is a valid record
+        unsafe { Self::take_raw(self) }
+    }
+
+    /// Reads the cause of the reset the record at `this` was left in.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for reads of a `ResetRecord`. The contents may be any value.
+    #[inline]
+    unsafe fn read(this: *const Self) -> ResetCause {
+        // volatile reads of plain words; the contents are validated before use
+        let magic = ptr::read_volatile(ptr::addr_of!((*this).magic));
+        let reason = ptr::read_volatile(ptr::addr_of!((*this).reason));
+        let check = ptr::read_volatile(ptr::addr_of!((*this).check));
+        if check != Self::check(magic, reason) {
+            return ResetCause::ColdBoot;
+        }
+        match magic {
+            MAGIC_SOFTWARE => ResetCause::Software(reason),
+            MAGIC_RUNNING => ResetCause::Watchdog,
+            _ => ResetCause::ColdBoot,
+        }
+    }
+
+    /// Reads the cause of the reset the record at `this` was left in, and arms it.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for reads and writes of a `ResetRecord`.
+    #[inline]
+    unsafe fn take_raw(this: *mut Self) -> ResetCause {
+        let cause = Self::read(this);
+        Self::write(this, MAGIC_RUNNING, 0);
+        cause
+    }
+
+    /// Writes `magic` and `reason` to the record at `this`.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for writes of a `ResetRecord`.
+    #[inline]
+    unsafe fn write(this: *mut Self, magic: u32, reason: u32) {
+        // volatile writes of plain words, the magic word last
+        ptr::write_volatile(ptr::addr_of_mut!((*this).reason), reason);
+        ptr::write_volatile(ptr::addr_of_mut!((*this).check), Self::check(magic, reason));
+        ptr::write_volatile(ptr::addr_of_mut!((*this).magic), magic);
+    }
+
+    #[inline(always)]
+    const fn check(magic: u32, reason: u32) -> u32 {
+        !(magic ^ reason.rotate_left(16))
+    }
+}
+
+impl Default for ResetRecord {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(cortex_m, link_section = ".uninit.cortex_m.RESET_RECORD")]
+static mut RESET_RECORD: MaybeUninit<ResetRecord> = MaybeUninit::uninit();
+
+/// Returns a pointer to the record.
+///
+/// The record is only accessed through this pointer, with volatile operations that tolerate any
+/// value, and never through a reference: an interrupt may record a reset while thread mode reads
+/// the record.
+#[inline(always)]
+fn record() -> *mut ResetRecord {
+    ptr::addr_of_mut!(RESET_RECORD).cast()
+}
+
+/// Returns the cause of the last reset, and arms the record for the next one.
+///
+/// This must be called once, early after reset: a second call returns
+/// [`ResetCause::Watchdog`].
+#[inline]
+pub fn take_cause() -> ResetCause {
+    // NOTE(unsafe) the record is valid for reads and writes of words
+    unsafe { ResetRecord::take_raw(record()) }
+}
+
+/// Records `reason` and initiates a system reset with [`SCB::sys_reset`].
+///
+/// After the reset, [`take_cause`] returns [`ResetCause::Software`] with `reason`.
+#[inline]
+pub fn software_reset(reason: u32) -> ! {
+    // NOTE(unsafe) the record is valid for writes of words
+    unsafe { ResetRecord::write(record(), MAGIC_SOFTWARE, reason) };
+    SCB::sys_reset()
+}
+
+/// Records `reason` and initiates a local reset of the core.
+///
+/// On ARMv7-M this is `SCB::vect_reset`, which leaves the peripherals of the MCU untouched.
+/// ARMv6-M and ARMv8-M do not implement a local reset, so a system reset is initiated instead.
+///
+/// # Safety
+///
+/// See `SCB::vect_reset`.
+#[inline]
+pub unsafe fn local_reset(reason: u32) -> ! {
+    ResetRecord::write(record(), MAGIC_SOFTWARE, reason);
+    #[cfg(all(not(armv6m), not(armv8m)))]
+    SCB::vect_reset();
+    #[cfg(any(armv6m, armv8m))]
+    SCB::sys_reset()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_record() {
+        let mut record = ResetRecord::new();
+        assert_eq!(record.cause(), ResetCause::ColdBoot);
+
+        assert_eq!(record.take(), ResetCause::ColdBoot);
+        assert_eq!(record.cause(), ResetCause::Watchdog);
+
+        record.set_reason(0xDEAD_BEEF);
+        assert_eq!(record.take(), ResetCause::Software(0xDEAD_BEEF));
+        assert_eq!(record.take(), ResetCause::Watchdog);
+
+        // the global record is only accessed through a raw pointer
+        let raw = ptr::addr_of_mut!(record);
+        unsafe {
+            ResetRecord::write(raw, MAGIC_SOFTWARE, 7);
+            assert_eq!(ResetRecord::take_raw(raw), ResetCause::Software(7));
+            assert_eq!(ResetRecord::read(raw), ResetCause::Watchdog);
+        }
+
+        // garbage left in RAM after a cold boot
+        let garbage: [u32; 3] = [0x5357_5253, 0x1234_5678, 0x9ABC_DEF0];
+        let record: ResetRecord = unsafe { core::mem::transmute(garbage) };
+        assert_eq!(record.cause(), ResetCause::ColdBoot);
+    }
+}
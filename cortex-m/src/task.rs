@@ -0,0 +1,494 @@
+//! Protected tasks
+//!
+//! [`psp::switch_to_unprivileged_psp`](crate::psp::switch_to_unprivileged_psp) drops privilege,
+//! but the unprivileged code can still access all the memory. A [`ProtectedTask`] also confines
+//! it with the MPU: the task runs unprivileged on its own [`Stack`](crate::psp::Stack), and can
+//! only access its stack and the regions it was given, typically its code, its data and the
+//! peripherals it drives.
+//!
+//! The MPU is enabled with the default memory map as background region for privileged accesses,
+//! so the exception handlers are not affected. An access of the task outside its regions raises a
+//! MemManage fault, which the `MemoryManagement` exception handler forwards to
+//! [`FaultHandler::handle_fault`]. A [`FaultPolicy`] then decides whether the task is killed,
+//! restarted, or whether the fault is reported to the handler.
+//!
+//! MemManage must be enabled with [`SCB::enable`](crate::peripheral::SCB::enable), or the faults
+//! escalate to HardFault.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cortex_m::peripheral::mpu::{AccessPermission, MemoryAttributes, MpuRegion};
+//! use cortex_m::peripheral::scb::Exception;
+//! use cortex_m::peripheral::Peripherals;
+//! use cortex_m::psp::Stack;
+//! use cortex_m::task::{FaultAction, FaultHandler, ProtectedTask};
+//!
+//! // the stack region must be aligned on its size
+//! #[repr(align(1024))]
+//! struct TaskStack(Stack<256>);
+//!
+//! static STACK: TaskStack = TaskStack(Stack::new());
+//!
+//! extern "C" fn worker() -> ! {
+//!     loop {
+//!         // ..
+//!     }
+//! }
+//!
+//! // called from the `MemoryManagement` exception handler
+//! fn on_mem_fault(handler: &FaultHandler) {
+//!     if let Some(_fault) = unsafe { handler.handle_fault(&mut FaultAction::Restart) } {
+//!         // not raised by the task
+//!         loop {}
+//!     }
+//! }
+//!
+//! let mut p = Peripherals::take().unwrap();
+//! let code = MpuRegion::new(0x0800_0000, 0x10_0000)
+//!     .unwrap()
+//!     .access(AccessPermission::ReadOnly);
+//! let uart = MpuRegion::new(0x4001_3800, 0x400)
+//!     .unwrap()
+//!     .attributes(MemoryAttributes::DEVICE)
+//!     .execute_never(true);
+//! let task = ProtectedTask::new(STACK.0.take_handle(), worker, [code, uart]).unwrap();
+//!
+//! // hand `faults` over to the `MemoryManagement` exception handler
+//! let faults = task.fault_handler();
+//!
+//! p.SCB.enable(Exception::MemoryManagement);
+//! task.activate(&mut p.MPU, 4).unwrap();
+//! # #[cfg(cortex_m)]
+//! unsafe { task.start() };
+//! ```
+
+use core::ptr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::peripheral::mpu::{MpuOptions, MpuRegion};
+use crate::peripheral::scb::{Cfsr, FaultStatus};
+use crate::peripheral::{MPU, SCB};
+use crate::psp::StackHandle;
+use crate::register::{control, psp};
+use cortex_m_types::result::{Error, Result};
+
+/// Smallest stack of a task, in bytes: an extended exception frame must fit
+const MIN_STACK_SIZE: u32 = 128;
+/// Words of an exception frame with the floating-point context
+const EXTENDED_FRAME_WORDS: usize = 26;
+/// Thumb bit of the stacked xPSR
+const XPSR_T: u32 = 1 << 24;
+/// MemManage bits of CFSR (MMFSR)
+const CFSR_MMFSR_MASK: u32 = 0xFF;
+/// No exception other than the current one is active
+const SCB_ICSR_RETTOBASE: u32 = 1 << 11;
+
+/// What to do with a task that raised a MemManage fault
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FaultAction {
+    /// Stops the task for good: on return from the handler, Thread mode is privileged again and
+    /// waits for interrupts forever.
+    Kill,
+    /// Restarts the task from its entry point, with an empty stack and the same regions.
+    Restart,
+    /// Leaves the task as is, and returns the fault from [`FaultHandler::handle_fault`]. The
+    /// handler must not return to the task, which would fault again: it typically logs the fault
+    /// and resets the system.
+    Report,
+}
+
+/// Decides what to do with a task that raised a MemManage fault
+///
+/// [`FaultAction`] implements the trait by always returning itself.
+pub trait FaultPolicy {
+    /// Called from the MemManage handler with the fault raised by the task.
+    fn on_fault(&mut self, fault: &TaskFault) -> FaultAction;
+}
+
+impl FaultPolicy for FaultAction {
+    #[inline]
+    fn on_fault(&mut self, _fault: &TaskFault) -> FaultAction {
+        *self
+    }
+}
+
+/// A MemManage fault, as seen by [`FaultHandler::handle_fault`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TaskFault {
+    /// MemManage fault causes, only the MemManage bits of CFSR are set
+    pub status: Cfsr,
+    /// Address of the faulting data access, if MMFAR is valid
+    pub address: Option<u32>,
+    /// Address of the faulting instruction, unless the fault occurred while stacking the
+    /// exception frame
+    pub pc: Option<u32>,
+    /// `true` if the fault was raised by the unprivileged Thread mode, that is by the task
+    pub in_task: bool,
+}
+
+/// A task running unprivileged on its own stack, confined by the MPU to its regions
+///
+/// The task uses `R + 1` consecutive MPU regions: its stack, read-write and execute-never, then
+/// the `R` regions given to [`ProtectedTask::new`]. Since the task runs without the default
+/// memory map, the regions must include the code it executes and the constant data it reads.
+///
+/// The task owns its stack, so it can only be started once.
+#[derive(Debug)]
+pub struct ProtectedTask<const R: usize> {
+    entry: extern "C" fn() -> !,
+    stack_top: u32,
+    stack: MpuRegion,
+    regions: [MpuRegion; R],
+}
+
+impl<const R: usize> ProtectedTask<R> {
+    /// Creates a task running `entry` on `stack`, with access to `regions`.
+    ///
+    /// Returns [`Error::InvalidValue`] if the stack is smaller than 128 bytes, with the bitmask of
+    /// the smallest size. The stack must also be a valid MPU region: on ARMv7-M its size must be
+    /// a power of two and it must be aligned on its size, on ARMv8-M it must be 32 bytes aligned
+    /// and its size a multiple of 32 bytes. Otherwise, the error of `MpuRegion::new` is returned.
+    #[inline]
+    pub fn new(
+        mut stack: StackHandle,
+        entry: extern "C" fn() -> !,
+        regions: [MpuRegion; R],
+    ) -> Result<Self> {
+        let stack_bottom = stack.bottom() as u32;
+        let stack_top = stack.top() as u32;
+        let size = stack_top - stack_bottom;
+        if size < MIN_STACK_SIZE {
+            return Err(Error::InvalidValue {
+                value: size as usize,
+                bitmask: !(MIN_STACK_SIZE - 1) as usize,
+            });
+        }
+
+        Ok(ProtectedTask {
+            entry,
+            stack_top,
+            stack: stack_region(stack_bottom, size)?,
+            regions,
+        })
+    }
+
+    /// Returns the MPU region of the stack of the task.
+    #[inline]
+    pub fn stack_region(&self) -> &MpuRegion {
+        &self.stack
+    }
+
+    /// Returns the MPU regions of the task, other than its stack.
+    #[inline]
+    pub fn regions(&self) -> &[MpuRegion; R] {
+        &self.regions
+    }
+
+    /// Programs the regions of the task into the `R + 1` MPU regions starting at `first`, and
+    /// enables the MPU with the default memory map as background region for privileged accesses.
+    ///
+    /// The regions of the task are disabled before being programmed, so another task using the
+    /// same region numbers can be switched out. Returns [`Error::IndexOutOfBounds`] if the
+    /// regions are not implemented, in which case the MPU is left untouched, or, on ARMv8-M,
+    /// [`Error::RegionOverlap`] if two regions overlap.
+    #[inline]
+    pub fn activate(&self, mpu: &mut MPU, first: u8) -> Result<()> {
+        let last = last_region(first, R, mpu.region_count())?;
+        for number in first..=last {
+            mpu.disable_region(number)?;
+        }
+        mpu.set_region(first, self.stack)?;
+        if R > 0 {
+            // `first + 1` does not overflow, as region `first + R` is implemented
+            mpu.set_regions(first + 1, &self.regions)?;
+        }
+
+        mpu.enable(MpuOptions {
+            privileged_default_map: true,
+            enabled_in_fault_handlers: false,
+        });
+        Ok(())
+    }
+
+    /// Returns the handler of the MemManage faults of the task.
+    ///
+    /// [`ProtectedTask::start`] consumes the task, so the handler must be obtained before.
+    #[inline]
+    pub fn fault_handler(&self) -> FaultHandler {
+        FaultHandler {
+            entry: self.entry,
+            stack_top: self.stack_top,
+        }
+    }
+
+    /// Switches to unprivileged Thread mode on the stack of the task, and runs it.
+    ///
+    /// # Safety
+    ///
+    /// Must be called from Thread mode, after [`ProtectedTask::activate`]. Started from an
+    /// exception handler, the task would replace PSP under the preempted Thread mode code.
+    #[cfg(cortex_m)]
+    #[inline]
+    pub unsafe fn start(self) -> ! {
+        // set the stack limit
+        #[cfg(armv8m_main)]
+        crate::register::psplim::write(self.stack.base());
+        // the stack belongs to the task, see `new`
+        crate::asm::enter_unprivileged_psp(self.stack_top as *const u32, self.entry)
+    }
+}
+
+/// Handles the MemManage faults of a [`ProtectedTask`]
+///
+/// Obtained with [`ProtectedTask::fault_handler`] before the task is started, and kept by the
+/// `MemoryManagement` exception handler.
+#[derive(Clone, Copy, Debug)]
+pub struct FaultHandler {
+    entry: extern "C" fn() -> !,
+    stack_top: u32,
+}
+
+impl FaultHandler {
+    /// Handles a MemManage fault, to be called from the `MemoryManagement` exception handler.
+    ///
+    /// If the fault was raised by the task, its status is cleared and `policy` decides what to
+    /// do: [`FaultAction::Kill`] and [`FaultAction::Restart`] return `None`, and the handler
+    /// must return for the action to take place. [`FaultAction::Report`] returns the fault.
+    ///
+    /// If the fault was not raised by the task, for example by an interrupt handler, `policy` is
+    /// not consulted, the status is left as is, and the fault is returned with `in_task` cleared.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the `MemoryManagement` exception handler, and the task of this
+    /// handler must be the one running in unprivileged Thread mode.
+    #[inline]
+    pub unsafe fn handle_fault<P>(&self, policy: &mut P) -> Option<TaskFault>
+    where
+        P: FaultPolicy,
+    {
+        let scb = &*SCB::PTR;
+        let status = FaultStatus::from_registers(
+            scb.cfsr.read() & CFSR_MMFSR_MASK,
+            0,
+            0,
+            scb.mmfar.read(),
+            0,
+            0,
+        );
+        let in_task =
+            scb.icsr.read() & SCB_ICSR_RETTOBASE != 0 && control::read().npriv().is_unprivileged();
+
+        let fault = TaskFault {
+            status: status.cfsr,
+            address: status.mmfar,
+            pc: if in_task && !status.cfsr.mstkerr() {
+                Some(ptr::read_volatile((psp::read() as *const u32).add(6)))
+            } else {
+                None
+            },
+            in_task,
+        };
+        if !in_task {
+            return Some(fault);
+        }
+
+        // NOTE(unsafe) write-one-to-clear register, only the reported bits are cleared
+        scb.cfsr.write(status.cfsr.bits());
+
+        match policy.on_fault(&fault) {
+            FaultAction::Kill => {
+                self.reset_frame(park as *const () as usize as u32);
+                let mut control = control::read();
+                control.set_npriv(control::Npriv::Privileged);
+                control::write(control);
+                crate::asm::isb();
+                None
+            }
+            FaultAction::Restart => {
+                self.reset_frame(self.entry as *const () as usize as u32);
+                None
+            }
+            FaultAction::Report => Some(fault),
+        }
+    }
+
+    /// Replaces the exception frame of the task with one returning to `pc` with an empty stack.
+    ///
+    /// Room is left for an extended frame: whether or not the floating-point context was stacked
+    /// on exception entry, the frame is valid, and the core unstacks it from the top of the
+    /// stack.
+    #[inline]
+    unsafe fn reset_frame(&self, pc: u32) {
+        let frame = (self.stack_top as *mut u32).sub(EXTENDED_FRAME_WORDS);
+        for i in 0..EXTENDED_FRAME_WORDS {
+            ptr::write_volatile(frame.add(i), 0);
+        }
+        // LR: the entry point of the task does not return
+        ptr::write_volatile(frame.add(5), 0xFFFF_FFFF);
+        ptr::write_volatile(frame.add(6), pc & !1);
+        ptr::write_volatile(frame.add(7), XPSR_T);
+        psp::write(frame as u32);
+    }
+}
+
+/// Where the Thread mode of a killed task ends up
+extern "C" fn park() -> ! {
+    loop {
+        crate::asm::wfi();
+    }
+}
+
+/// Returns the last of the MPU regions of a task with `regions` regions besides its stack,
+/// starting at `first`.
+///
+/// Returns [`Error::IndexOutOfBounds`] if that region is not one of the `implemented` ones.
+#[inline]
+fn last_region(first: u8, regions: usize, implemented: u8) -> Result<u8> {
+    let last = usize::from(first) + regions;
+    if last < usize::from(implemented) {
+        // `last` fits in a `u8`, as it is below `implemented`
+        Ok(last as u8)
+    } else {
+        Err(Error::IndexOutOfBounds {
+            index: last,
+            min: 0,
+            max: usize::from(implemented).saturating_sub(1),
+        })
+    }
+}
+
+/// Returns the MPU region of a stack.
+#[cfg(not(armv8m))]
+#[inline]
+fn stack_region(bottom: u32, size: u32) -> Result<MpuRegion> {
+    use crate::peripheral::mpu::AccessPermission;
+
+    Ok(MpuRegion::new(bottom, size)?
+        .access(AccessPermission::ReadWrite)
+        .execute_never(true))
+}
+
+/// Returns the MPU region of a stack.
+///
+/// The region uses the memory attribute at index 0 of the MAIR registers.
+#[cfg(armv8m)]
+#[inline]
+fn stack_region(bottom: u32, size: u32) -> Result<MpuRegion> {
+    use crate::peripheral::mpu::AccessPermission;
+
+    Ok(MpuRegion::new(bottom, bottom + size - 1)?
+        .access(AccessPermission::ReadWrite)
+        .execute_never(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protected_task() {
+        use crate::peripheral::mpu::AccessPermission;
+        use crate::psp::Stack;
+
+        #[repr(align(1024))]
+        struct Aligned(Stack<256>);
+
+        // 32 bytes past a 1 KiB boundary
+        #[repr(C, align(1024))]
+        struct Unaligned([u32; 8], Stack<256>);
+
+        static STACK: Aligned = Aligned(Stack::new());
+        static EMPTY: Aligned = Aligned(Stack::new());
+        static UNALIGNED: Unaligned = Unaligned([0; 8], Stack::new());
+        static TINY: Stack<16> = Stack::new();
+
+        extern "C" fn entry() -> ! {
+            unreachable!()
+        }
+
+        let code = MpuRegion::new(0x0800_0000, 0x10_0000)
+            .unwrap()
+            .access(AccessPermission::ReadOnly);
+        let task = ProtectedTask::new(STACK.0.take_handle(), entry, [code]).unwrap();
+        let stack = task.stack_region();
+        assert_eq!(stack.base(), address(&STACK) as u32);
+        assert_eq!(stack.size(), 1024);
+        assert_eq!(
+            stack.rasr() >> 24 & 0b111,
+            AccessPermission::ReadWrite as u32
+        );
+        assert_ne!(stack.rasr() & 1 << 28, 0);
+        assert_eq!(task.regions(), &[code]);
+        let faults = task.fault_handler();
+        assert_eq!(faults.stack_top, address(&STACK) as u32 + 1024);
+        assert_eq!(faults.entry as usize, entry as *const () as usize);
+
+        // 1 KiB stacks need a 1 KiB alignment
+        assert_eq!(address(&UNALIGNED.1) & 0x3FF, 32);
+        assert!(matches!(
+            ProtectedTask::new(UNALIGNED.1.take_handle(), entry, []),
+            Err(Error::InvalidFieldValue { field: "ADDR", .. })
+        ));
+        assert_eq!(
+            ProtectedTask::new(TINY.take_handle(), entry, []).err(),
+            Some(Error::InvalidValue {
+                value: 64,
+                bitmask: !0x7F_u32 as usize
+            })
+        );
+        assert!(ProtectedTask::<0>::new(EMPTY.0.take_handle(), entry, []).is_ok());
+
+        let fault = TaskFault {
+            status: crate::peripheral::scb::FaultStatus::from_registers(0b1000_0010, 0, 0, 0, 0, 0)
+                .cfsr,
+            address: Some(0x2000_0000),
+            pc: Some(0x0800_0100),
+            in_task: true,
+        };
+        assert!(fault.status.daccviol());
+        assert_eq!(FaultAction::Restart.on_fault(&fault), FaultAction::Restart);
+    }
+
+    #[test]
+    fn task_regions() {
+        // a task without regions besides its stack fits in the top region
+        assert_eq!(last_region(7, 0, 8), Ok(7));
+        assert_eq!(last_region(6, 1, 8), Ok(7));
+        assert_eq!(
+            last_region(7, 1, 8),
+            Err(Error::IndexOutOfBounds {
+                index: 8,
+                min: 0,
+                max: 7
+            })
+        );
+        assert_eq!(
+            last_region(255, 0, 255),
+            Err(Error::IndexOutOfBounds {
+                index: 255,
+                min: 0,
+                max: 254
+            })
+        );
+        assert_eq!(
+            last_region(0, 0, 0),
+            Err(Error::IndexOutOfBounds {
+                index: 0,
+                min: 0,
+                max: 0
+            })
+        );
+    }
+
+    fn address<T>(r: *const T) -> usize {
+        r as usize
+    }
+}
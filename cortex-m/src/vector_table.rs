@@ -0,0 +1,306 @@
+//! Vector table in RAM
+//!
+//! The vector table built by `cortex-m-rt` lives in Flash, so its handlers are fixed at link time.
+//! A [`VectorTable`] is a copy of that table in RAM: once VTOR points to it, handlers can be
+//! registered and unregistered at runtime.
+//!
+//! The table is usually placed in the `.uninit` section, so that it is neither zeroed nor copied
+//! at startup, and initialized from the active table. VTOR requires the table to be aligned on its
+//! size rounded up to a power of two, see [`VectorTable::ALIGN`]. A [`VectorTable`] is only aligned
+//! on 128 bytes, enough for 32 entries, so larger tables are wrapped in a type with the required
+//! alignment:
+//!
+//! ```no_run
+//! use core::mem::MaybeUninit;
+//! use core::ptr::addr_of_mut;
+//! use cortex_m::peripheral::Peripherals;
+//! use cortex_m::vector_table::VectorTable;
+//! # #[derive(Clone, Copy)]
+//! # enum Interrupt { Uart0 = 3 }
+//! # unsafe impl cortex_m::InterruptNumber for Interrupt {
+//! #     const MAX_INTERRUPT_NUMBER: usize = 31;
+//! #     fn number(self) -> usize { self as usize }
+//! #     fn from_number(n: usize) -> cortex_m::result::Result<Self> {
+//! #         Err(cortex_m::result::Error::InvalidVariant(n))
+//! #     }
+//! # }
+//!
+//! // 16 exceptions and 32 interrupts: 192 bytes, aligned on 256 bytes
+//! #[repr(C, align(256))]
+//! struct Vectors(MaybeUninit<VectorTable<48>>);
+//!
+//! #[link_section = ".uninit.VECTORS"]
+//! static mut VECTORS: Vectors = Vectors(MaybeUninit::uninit());
+//!
+//! extern "C" fn uart0() {
+//!     // ..
+//! }
+//!
+//! let mut p = Peripherals::take().unwrap();
+//! let table = unsafe { VectorTable::init_from_active(&mut *addr_of_mut!(VECTORS.0)) };
+//! unsafe { table.activate(&mut p.SCB) };
+//!
+//! table.register(Interrupt::Uart0, uart0);
+//! // ..
+//! table.unregister(Interrupt::Uart0);
+//! ```
+
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+
+use crate::peripheral::nvic::NVIC_MAX_INTERRUPTS;
+use crate::peripheral::SCB;
+use crate::{ExceptionNumber, InterruptNumber};
+use cortex_m_types::result::{Error, Result};
+
+/// An exception or interrupt handler
+pub type Handler = extern "C" fn();
+
+/// Number of entries before the first interrupt: the initial stack pointer and the 15 exceptions
+const EXCEPTIONS: usize = 16;
+
+/// A vector table of `N` entries, including the initial stack pointer and the 15 exceptions
+///
+/// The table is aligned on 128 bytes, the minimum alignment required by VTOR. Tables of more than
+/// 32 entries need a larger alignment, [`VectorTable::ALIGN`], which is checked when the table is
+/// activated.
+#[repr(C, align(128))]
+pub struct VectorTable<const N: usize> {
+    entries: [usize; N],
+    source: *const usize,
+}
+
+// NOTE(unsafe) `source` is only read, and points to a vector table that is never deallocated
+unsafe impl<const N: usize> Send for VectorTable<N> {}
+
+impl<const N: usize> VectorTable<N> {
+    /// Alignment required by VTOR: the size of the table rounded up to a power of two, and at
+    /// least 128 bytes.
+    pub const ALIGN: usize = {
+        let size = mem::size_of::<[usize; N]>().next_power_of_two();
+        if size < 128 {
+            128
+        } else {
+            size
+        }
+    };
+
+    /// Initializes `table` with a copy of the `N` entries of the vector table at `source`.
+    ///
+    /// [`VectorTable::unregister`] restores the entries of `source`.
+    ///
+    /// # Safety
+    ///
+    /// `source` must point to a valid vector table of at least `N` entries, which must remain
+    /// valid as long as the returned table is in use.
+    #[inline]
+    pub unsafe fn init_from(table: &mut MaybeUninit<Self>, source: *const usize) -> &mut Self {
+        const {
+            assert!(
+                N > EXCEPTIONS && N <= EXCEPTIONS + NVIC_MAX_INTERRUPTS,
+                "a vector table holds 16 exceptions and the interrupts supported by the NVIC"
+            )
+        };
+
+        let this = table.as_mut_ptr();
+        let entries = ptr::addr_of_mut!((*this).entries) as *mut usize;
+        for i in 0..N {
+            entries.add(i).write_volatile(source.add(i).read_volatile());
+        }
+        ptr::addr_of_mut!((*this).source).write(source);
+
+        // NOTE(unsafe) all the fields have been written
+        table.assume_init_mut()
+    }
+
+    /// Initializes `table` with a copy of the vector table VTOR currently points to.
+    ///
+    /// # Safety
+    ///
+    /// The active vector table must have at least `N` entries. See also
+    /// [`VectorTable::init_from`].
+    #[inline]
+    pub unsafe fn init_from_active(table: &mut MaybeUninit<Self>) -> &mut Self {
+        let vtor = (*SCB::PTR).vtor.read();
+        Self::init_from(table, vtor as *const usize)
+    }
+
+    /// Points VTOR to this table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is not aligned on [`VectorTable::ALIGN`] bytes.
+    ///
+    /// # Safety
+    ///
+    /// From now on the handlers of this table are used. The table must stay in place, and must
+    /// not be initialized again, until VTOR is pointed somewhere else.
+    #[inline]
+    pub unsafe fn activate(&self, scb: &mut SCB) {
+        if self.try_activate(scb).is_err() {
+            panic!("the vector table is not aligned on its size rounded up to a power of two");
+        }
+    }
+
+    /// Points VTOR to this table.
+    ///
+    /// Same as [`VectorTable::activate`], but returns [`Error::InvalidValue`] instead of
+    /// activating the table if it is not aligned on [`VectorTable::ALIGN`] bytes.
+    ///
+    /// # Safety
+    ///
+    /// See [`VectorTable::activate`].
+    #[inline]
+    pub unsafe fn try_activate(&self, scb: &mut SCB) -> Result<()> {
+        self.check_alignment()?;
+
+        crate::asm::dsb();
+        scb.vtor.write(self.entries.as_ptr() as u32);
+        crate::asm::dsb();
+        crate::asm::isb();
+        Ok(())
+    }
+
+    /// Returns `true` if VTOR points to this table.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        // NOTE(unsafe) atomic read with no side effects
+        let vtor = unsafe { (*SCB::PTR).vtor.read() };
+        vtor == self.entries.as_ptr() as u32
+    }
+
+    /// Sets the handler of `interrupt`, and returns the address of the previous handler.
+    ///
+    /// It is checked at compile time that all the interrupts of `I` fit in the table.
+    #[inline]
+    pub fn register<I>(&mut self, interrupt: I, handler: Handler) -> usize
+    where
+        I: InterruptNumber,
+    {
+        let index = Self::interrupt_index(interrupt);
+        self.replace(index, handler as usize)
+    }
+
+    /// Restores the handler `interrupt` had in the source table, and returns the address of the
+    /// previous handler.
+    #[inline]
+    pub fn unregister<I>(&mut self, interrupt: I) -> usize
+    where
+        I: InterruptNumber,
+    {
+        let index = Self::interrupt_index(interrupt);
+        self.restore(index)
+    }
+
+    /// Sets the handler of `exception`, and returns the address of the previous handler.
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if `exception` is `0` (the initial stack pointer) or
+    /// `1` (the reset handler), or does not fit in the table.
+    ///
+    /// # Safety
+    ///
+    /// Exception handlers such as `HardFault` or `SVCall` may be relied upon by the rest of the
+    /// program, and are called with arguments in registers and on the stack that `handler` must
+    /// cope with.
+    #[inline]
+    pub unsafe fn register_exception<E>(&mut self, exception: E, handler: Handler) -> Result<usize>
+    where
+        E: ExceptionNumber,
+    {
+        let index = Self::exception_index(exception)?;
+        Ok(self.replace(index, handler as usize))
+    }
+
+    /// Restores the handler `exception` had in the source table, and returns the address of the
+    /// previous handler.
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if `exception` is `0`, `1`, or does not fit in the
+    /// table.
+    ///
+    /// # Safety
+    ///
+    /// See [`VectorTable::register_exception`].
+    #[inline]
+    pub unsafe fn unregister_exception<E>(&mut self, exception: E) -> Result<usize>
+    where
+        E: ExceptionNumber,
+    {
+        let index = Self::exception_index(exception)?;
+        Ok(self.restore(index))
+    }
+
+    /// Returns the address of the handler of `interrupt`.
+    #[inline]
+    pub fn handler<I>(&self, interrupt: I) -> usize
+    where
+        I: InterruptNumber,
+    {
+        let index = Self::interrupt_index(interrupt);
+        self.entries[index]
+    }
+
+    /// Returns the entries of the table.
+    #[inline]
+    pub fn entries(&self) -> &[usize; N] {
+        &self.entries
+    }
+
+    /// Returns [`Error::InvalidValue`] if the table is not aligned on [`VectorTable::ALIGN`] bytes.
+    #[inline]
+    pub(crate) fn check_alignment(&self) -> Result<()> {
+        let address = self.entries.as_ptr() as usize;
+        if address % Self::ALIGN != 0 {
+            return Err(Error::InvalidValue {
+                value: address,
+                bitmask: !(Self::ALIGN as u32 - 1) as usize,
+            });
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn replace(&mut self, index: usize, address: usize) -> usize {
+        let entry = &mut self.entries[index];
+        let previous = *entry;
+        // NOTE(unsafe) the hardware may read the entry at any time, so it is written in a single
+        // word access
+        unsafe { ptr::write_volatile(entry, address) };
+        previous
+    }
+
+    #[inline]
+    fn restore(&mut self, index: usize) -> usize {
+        // NOTE(unsafe) `source` is valid for `N` entries, see `init_from`
+        let address = unsafe { self.source.add(index).read_volatile() };
+        self.replace(index, address)
+    }
+
+    #[inline(always)]
+    fn interrupt_index<I>(interrupt: I) -> usize
+    where
+        I: InterruptNumber,
+    {
+        const {
+            assert!(
+                I::MAX_INTERRUPT_NUMBER + EXCEPTIONS < N,
+                "MAX_INTERRUPT_NUMBER does not fit in the vector table"
+            )
+        };
+        EXCEPTIONS + interrupt.number()
+    }
+
+    #[inline]
+    fn exception_index<E>(exception: E) -> Result<usize>
+    where
+        E: ExceptionNumber,
+    {
+        match exception.number() {
+            index @ 2..EXCEPTIONS => Ok(index),
+            index => Err(Error::IndexOutOfBounds {
+                index,
+                min: 2,
+                max: EXCEPTIONS - 1,
+            }),
+        }
+    }
+}
@@ -218,23 +218,65 @@ pub fn check_blobs() {
     println!("Blobs identical.");
 }
 
-// Check that serde and PartialOrd works with VectActive
+// Check that serde and PartialOrd works with VectActive, and serde with register values
 pub fn check_host_side() {
-    use cortex_m::peripheral::scb::VectActive;
+    use cortex_m::peripheral::cpuid::CsselrCacheType;
+    use cortex_m::peripheral::scb::{Exception, VectActive};
+    use cortex_m::peripheral::snapshot::Snapshot;
+    use cortex_m::peripheral::syst::SystClkSource;
+    use cortex_m::register::control::Control;
 
     // check serde
     {
-        let v = VectActive::from(22).unwrap();
+        let v = VectActive::<Exception>::from(22).unwrap();
         let json = serde_json::to_string(&v).expect("Failed to serialize VectActive");
         let deser_v: VectActive =
             serde_json::from_str(&json).expect("Failed to deserialize VectActive");
         assert_eq!(deser_v, v);
     }
 
+    // check serde of register values
+    {
+        let control = Control::from_bits(0b011);
+        let json = serde_json::to_string(&control).expect("Failed to serialize Control");
+        let deser_control: Control =
+            serde_json::from_str(&json).expect("Failed to deserialize Control");
+        assert_eq!(deser_control.bits(), control.bits());
+
+        let values = (SystClkSource::Core, CsselrCacheType::Instruction);
+        let json = serde_json::to_string(&values).expect("Failed to serialize register values");
+        let deser_values: (SystClkSource, CsselrCacheType) =
+            serde_json::from_str(&json).expect("Failed to deserialize register values");
+        assert_eq!(deser_values, values);
+    }
+
+    // check snapshot JSON round trip and diff against a golden reference
+    {
+        let mut golden = Snapshot::default();
+        golden.scb.vtor = 0x0800_0000;
+        golden.nvic.ipr[100] = 0x8040_2000;
+        let json = golden.to_json().expect("Failed to serialize Snapshot");
+        let device = Snapshot::from_bytes(&golden.to_bytes()).expect("Invalid snapshot dump");
+        assert_eq!(
+            Snapshot::from_json(&json).expect("Failed to deserialize Snapshot"),
+            device
+        );
+
+        let mut differences = Vec::new();
+        golden.diff(&Snapshot::default(), |d| differences.push(d.to_string()));
+        assert_eq!(
+            differences,
+            [
+                "SCB.VTOR: 0x08000000 != 0x00000000",
+                "NVIC.IPR[100]: 0x80402000 != 0x00000000"
+            ]
+        );
+    }
+
     // check PartialOrd
     {
-        let a = VectActive::from(19).unwrap();
-        let b = VectActive::from(20).unwrap();
+        let a = VectActive::<Exception>::from(19).unwrap();
+        let b = VectActive::<Exception>::from(20).unwrap();
         assert!(a < b);
     }
 }